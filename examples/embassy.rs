@@ -5,12 +5,12 @@
 
 use embassy_executor::Spawner;
 use linux_embedded_hal::I2cdev;
-use tcs3472::{RgbCInterruptPersistence, Tcs3472};
+use tcs3472::Tcs3472Async;
 
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) {
     let dev = I2cdev::new("/dev/i2c-1").unwrap();
-    let mut sensor = Tcs3472::new(dev);
+    let mut sensor = Tcs3472Async::new(dev);
     sensor.enable().await.unwrap();
     sensor.enable_rgbc().await.unwrap();
     while !sensor.is_rgbc_status_valid().await.unwrap() {
@@ -0,0 +1,210 @@
+//! Management of several sensors as an array.
+
+use crate::interface::CYCLE_TIME_US;
+#[cfg(feature = "async")]
+use crate::Tcs3472Async;
+use crate::{AllChannelMeasurement, Config, Error, Tcs3472};
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+#[cfg(feature = "async")]
+use embedded_hal_async::{delay::DelayNs as AsyncDelayNs, i2c::I2c as AsyncI2c};
+
+/// Error of an operation on a sensor array
+#[derive(Debug)]
+pub struct SensorArrayError<E> {
+    /// Index of the sensor where the error occurred.
+    pub index: usize,
+    /// Error that occurred.
+    pub error: Error<E>,
+}
+
+/// Measurement of a sensor in an array with its timestamps
+///
+/// The timestamps are taken with the timestamp source given to
+/// [`SensorArray::trigger()`] and have its unit (e.g. microseconds).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimedMeasurement {
+    /// Measurement of all channels.
+    pub measurement: AllChannelMeasurement,
+    /// Time at which the RGB converter was enabled.
+    pub started_at: u64,
+    /// Time at which the measurement was read.
+    pub read_at: u64,
+    /// Estimated start of the integration relative to the sensor that
+    /// started first.
+    pub skew: u64,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(self = "SensorArray"),
+    async(
+        feature = "async",
+        self = "SensorArrayAsync",
+        idents(Tcs3472(async = "Tcs3472Async"))
+    )
+)]
+/// Manager of several TCS3472 devices.
+///
+/// The drivers usually talk to their device through an [`I2cMux`](crate::I2cMux)
+/// channel or another shared-bus wrapper. Operations are performed on the
+/// sensors in index order and stop at the first error, which reports the
+/// index of the failing sensor.
+///
+/// `SensorArray` manages `Tcs3472` drivers. If the `async` feature is
+/// enabled, `SensorArrayAsync` manages `Tcs3472Async` drivers in the same
+/// way.
+#[derive(Debug)]
+pub struct SensorArray<I2C, const N: usize> {
+    sensors: [Tcs3472<I2C>; N],
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        self = "SensorArray",
+        idents(AsyncI2c(sync = "I2c"), AsyncDelayNs(sync = "DelayNs"))
+    ),
+    async(
+        feature = "async",
+        self = "SensorArrayAsync",
+        idents(Tcs3472(async = "Tcs3472Async"))
+    )
+)]
+impl<I2C, E, const N: usize> SensorArray<I2C, N>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Create a new sensor array.
+    pub fn new(sensors: [Tcs3472<I2C>; N]) -> Self {
+        SensorArray { sensors }
+    }
+
+    /// Get a sensor driver.
+    ///
+    /// Returns `None` if the index is out of range.
+    pub fn sensor_mut(&mut self, index: usize) -> Option<&mut Tcs3472<I2C>> {
+        self.sensors.get_mut(index)
+    }
+
+    /// Apply the same configuration to all sensors.
+    ///
+    /// See [`Tcs3472::apply_config()`].
+    pub async fn apply_config(&mut self, config: &Config) -> Result<(), SensorArrayError<E>> {
+        for (index, sensor) in self.sensors.iter_mut().enumerate() {
+            sensor
+                .apply_config(config)
+                .await
+                .map_err(|error| SensorArrayError { index, error })?;
+        }
+        Ok(())
+    }
+
+    /// Apply a configuration to each sensor.
+    ///
+    /// The configuration at each index is applied to the sensor at the
+    /// same index.
+    pub async fn apply_configs(
+        &mut self,
+        configs: &[Config; N],
+    ) -> Result<(), SensorArrayError<E>> {
+        for (index, (sensor, config)) in self.sensors.iter_mut().zip(configs).enumerate() {
+            sensor
+                .apply_config(config)
+                .await
+                .map_err(|error| SensorArrayError { index, error })?;
+        }
+        Ok(())
+    }
+
+    /// Read the measurement data of all channels of all sensors.
+    ///
+    /// The sensors are read back to back, each with a single transaction.
+    pub async fn read_all_channels(
+        &mut self,
+    ) -> Result<[AllChannelMeasurement; N], SensorArrayError<E>> {
+        let mut measurements = [AllChannelMeasurement::default(); N];
+        for (index, (sensor, measurement)) in
+            self.sensors.iter_mut().zip(&mut measurements).enumerate()
+        {
+            *measurement = sensor
+                .read_all_channels()
+                .await
+                .map_err(|error| SensorArrayError { index, error })?;
+        }
+        Ok(measurements)
+    }
+
+    /// Measure with all sensors over the same time window.
+    ///
+    /// This powers all sensors on with the RGB converter disabled, waits
+    /// for the warm-up time and then enables the RGB converters back to
+    /// back so that the integrations start as close together as possible.
+    /// After waiting for the longest configured integration time, the
+    /// status of each sensor is polled until its measurement is valid
+    /// and the measurement is read. The integration time of a sensor is
+    /// read from the device first if it is not known.
+    ///
+    /// The `timestamp` source is called right after enabling the RGB
+    /// converter and right after reading the measurement of each sensor.
+    /// The skew of each sensor is the time its RGB converter was enabled
+    /// after the first one. If a measurement does not become valid within
+    /// 10% of the integration time plus 4 cycles, `Error::Timeout` is
+    /// returned.
+    ///
+    /// If `power_down` is `true`, each sensor is put to sleep after its
+    /// measurement is read.
+    pub async fn trigger<D: AsyncDelayNs, T: FnMut() -> u64>(
+        &mut self,
+        delay: &mut D,
+        mut timestamp: T,
+        power_down: bool,
+    ) -> Result<[TimedMeasurement; N], SensorArrayError<E>> {
+        let mut cycles = 1;
+        for (index, sensor) in self.sensors.iter_mut().enumerate() {
+            let sensor_cycles = sensor
+                .timing_integration_cycles()
+                .await
+                .map_err(|error| SensorArrayError { index, error })?;
+            cycles = cycles.max(sensor_cycles);
+            sensor
+                .power_on_rgbc_disabled()
+                .await
+                .map_err(|error| SensorArrayError { index, error })?;
+        }
+        delay.delay_us(CYCLE_TIME_US).await;
+        let mut results = [TimedMeasurement::default(); N];
+        for (index, (sensor, result)) in self.sensors.iter_mut().zip(&mut results).enumerate() {
+            sensor
+                .enable_rgbc()
+                .await
+                .map_err(|error| SensorArrayError { index, error })?;
+            result.started_at = timestamp();
+        }
+        delay.delay_us(u32::from(cycles) * CYCLE_TIME_US).await;
+        for (index, (sensor, result)) in self.sensors.iter_mut().zip(&mut results).enumerate() {
+            sensor
+                .wait_for_rgbc_status_valid(delay, cycles)
+                .await
+                .map_err(|error| SensorArrayError { index, error })?;
+            result.measurement = sensor
+                .read_all_channels()
+                .await
+                .map_err(|error| SensorArrayError { index, error })?;
+            result.read_at = timestamp();
+            if power_down {
+                sensor
+                    .power_off()
+                    .await
+                    .map_err(|error| SensorArrayError { index, error })?;
+            }
+        }
+        let first = results.iter().map(|r| r.started_at).min().unwrap_or(0);
+        for result in &mut results {
+            result.skew = result.started_at.saturating_sub(first);
+        }
+        Ok(results)
+    }
+
+    /// Destroy sensor array, return the sensor drivers.
+    pub fn destroy(self) -> [Tcs3472<I2C>; N] {
+        self.sensors
+    }
+}
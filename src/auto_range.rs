@@ -0,0 +1,250 @@
+//! Automatic gain and integration time ranging.
+
+#[cfg(feature = "async")]
+use crate::Tcs3472Async;
+use crate::{types::full_scale_count, AllChannelMeasurement, Error, RgbCGain, Tcs3472};
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Automatic gain and integration time ranging controller
+///
+/// The controller keeps the clear channel measurement within a target
+/// window of the full-scale count by stepping the RGB converter gain and
+/// the number of integration cycles. When the measurement is too dark,
+/// the integration time is increased first (up to the configured maximum)
+/// and then the gain. When the measurement is too bright, the gain is
+/// decreased first and then the integration time.
+///
+/// The first measurement after a settings change is discarded because the
+/// integration cycle in progress spans the change.
+///
+/// See [`Tcs3472::read_all_channels_auto_ranged()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AutoRange {
+    gain: RgbCGain,
+    integration_cycles: u16,
+    min_integration_cycles: u16,
+    max_integration_cycles: u16,
+    low_percent: u8,
+    high_percent: u8,
+    apply_pending: bool,
+    discard_next: bool,
+}
+
+impl Default for AutoRange {
+    fn default() -> Self {
+        AutoRange {
+            gain: RgbCGain::_1x,
+            integration_cycles: 64,
+            min_integration_cycles: 1,
+            max_integration_cycles: 256,
+            low_percent: 10,
+            high_percent: 70,
+            apply_pending: true,
+            discard_next: false,
+        }
+    }
+}
+
+impl AutoRange {
+    /// Create a new controller.
+    ///
+    /// It starts with 1x gain and 64 integration cycles, a target window of
+    /// 10% to 70% of the full-scale count and allows 1 to 256 integration
+    /// cycles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the target window for the clear channel as percentage of the
+    /// full-scale count.
+    ///
+    /// The window must be wide enough to accommodate a 4x sensitivity step,
+    /// otherwise the controller may oscillate.
+    /// Values are limited to 100% and `low_percent` to be below `high_percent`.
+    pub fn with_target_window(mut self, low_percent: u8, high_percent: u8) -> Self {
+        self.high_percent = high_percent.clamp(1, 100);
+        self.low_percent = low_percent.min(self.high_percent - 1);
+        self
+    }
+
+    /// Set the initial RGB converter gain and number of integration cycles.
+    ///
+    /// The number of integration cycles is limited to the configured range.
+    pub fn with_initial_settings(mut self, gain: RgbCGain, integration_cycles: u16) -> Self {
+        self.gain = gain;
+        self.integration_cycles =
+            integration_cycles.clamp(self.min_integration_cycles, self.max_integration_cycles);
+        self
+    }
+
+    /// Set the range of integration cycles (1-256) the controller may use.
+    ///
+    /// Longer integration times improve the resolution in dark conditions
+    /// at the cost of a lower measurement rate.
+    pub fn with_integration_cycles_range(mut self, min_cycles: u16, max_cycles: u16) -> Self {
+        self.max_integration_cycles = max_cycles.clamp(1, 256);
+        self.min_integration_cycles = min_cycles.clamp(1, self.max_integration_cycles);
+        self.integration_cycles = self
+            .integration_cycles
+            .clamp(self.min_integration_cycles, self.max_integration_cycles);
+        self
+    }
+
+    /// Current RGB converter gain.
+    pub fn gain(&self) -> RgbCGain {
+        self.gain
+    }
+
+    /// Current number of integration cycles.
+    pub fn integration_cycles(&self) -> u16 {
+        self.integration_cycles
+    }
+
+    /// Step the settings according to the clear channel value.
+    ///
+    /// Returns whether the settings were changed.
+    fn adjust(&mut self, clear: u16) -> bool {
+        let full_scale = u32::from(full_scale_count(self.integration_cycles));
+        let clear = u32::from(clear);
+        if clear * 100 > full_scale * u32::from(self.high_percent) {
+            self.step_down()
+        } else if clear * 100 < full_scale * u32::from(self.low_percent) {
+            self.step_up()
+        } else {
+            false
+        }
+    }
+
+    fn step_up(&mut self) -> bool {
+        if self.integration_cycles < self.max_integration_cycles {
+            self.integration_cycles =
+                (self.integration_cycles * 4).min(self.max_integration_cycles);
+            true
+        } else {
+            let gain = match self.gain {
+                RgbCGain::_1x => RgbCGain::_4x,
+                RgbCGain::_4x => RgbCGain::_16x,
+                RgbCGain::_16x | RgbCGain::_60x => RgbCGain::_60x,
+            };
+            let changed = gain != self.gain;
+            self.gain = gain;
+            changed
+        }
+    }
+
+    fn step_down(&mut self) -> bool {
+        if self.gain != RgbCGain::_1x {
+            self.gain = match self.gain {
+                RgbCGain::_60x => RgbCGain::_16x,
+                RgbCGain::_16x => RgbCGain::_4x,
+                RgbCGain::_4x | RgbCGain::_1x => RgbCGain::_1x,
+            };
+            true
+        } else if self.integration_cycles > self.min_integration_cycles {
+            self.integration_cycles =
+                (self.integration_cycles / 4).max(self.min_integration_cycles);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Measurement taken by the automatic ranging controller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AutoRangedMeasurement {
+    /// Raw measurement of all channels.
+    pub measurement: AllChannelMeasurement,
+    /// RGB converter gain used for the measurement.
+    pub gain: RgbCGain,
+    /// Number of integration cycles used for the measurement.
+    pub integration_cycles: u16,
+}
+
+/// Measurement normalized to 1x gain and a single integration cycle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NormalizedMeasurement {
+    /// Red channel counts.
+    pub red: f32,
+    /// Green channel counts.
+    pub green: f32,
+    /// Blue channel counts.
+    pub blue: f32,
+    /// Clear (unfiltered) channel counts.
+    pub clear: f32,
+}
+
+impl AutoRangedMeasurement {
+    /// Measurement normalized to 1x gain and a single integration cycle.
+    ///
+    /// This makes measurements taken with different settings comparable.
+    pub fn normalized(&self) -> NormalizedMeasurement {
+        let factor = f32::from(self.gain.multiplier()) * f32::from(self.integration_cycles);
+        NormalizedMeasurement {
+            red: f32::from(self.measurement.red) / factor,
+            green: f32::from(self.measurement.green) / factor,
+            blue: f32::from(self.measurement.blue) / factor,
+            clear: f32::from(self.measurement.clear) / factor,
+        }
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(self = "Tcs3472", idents(AsyncI2c(sync = "I2c"))),
+    async(feature = "async", self = "Tcs3472Async")
+)]
+impl<I2C, E> Tcs3472<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Read the measurement data of all channels with automatic ranging.
+    ///
+    /// This should be called once per RGBC cycle, for example after the
+    /// integration time has elapsed or on an RGBC interrupt.
+    ///
+    /// The first call applies the initial settings of the controller.
+    /// Returns `None` if no valid measurement is available yet, if the
+    /// measurement was discarded after a settings change or if the
+    /// measurement was out of the target window and the settings were
+    /// changed. Once the settings are at their limits, measurements out of
+    /// the target window are returned as well.
+    pub async fn read_all_channels_auto_ranged(
+        &mut self,
+        auto_range: &mut AutoRange,
+    ) -> Result<Option<AutoRangedMeasurement>, Error<E>> {
+        if auto_range.apply_pending {
+            self.apply_auto_range(auto_range).await?;
+            return Ok(None);
+        }
+        if !self.is_rgbc_status_valid().await? {
+            return Ok(None);
+        }
+        let measurement = self.read_all_channels().await?;
+        if auto_range.discard_next {
+            auto_range.discard_next = false;
+            return Ok(None);
+        }
+        let gain = auto_range.gain;
+        let integration_cycles = auto_range.integration_cycles;
+        if auto_range.adjust(measurement.clear) {
+            self.apply_auto_range(auto_range).await?;
+            return Ok(None);
+        }
+        Ok(Some(AutoRangedMeasurement {
+            measurement,
+            gain,
+            integration_cycles,
+        }))
+    }
+
+    async fn apply_auto_range(&mut self, auto_range: &mut AutoRange) -> Result<(), Error<E>> {
+        self.set_rgbc_gain(auto_range.gain).await?;
+        self.set_integration_cycles(auto_range.integration_cycles)
+            .await?;
+        auto_range.apply_pending = false;
+        auto_range.discard_next = true;
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+//! Least-squares calibration of the XYZ conversion matrix.
+
+use crate::{AllChannelMeasurement, CalibrationMatrix, Xyz};
+
+/// Calibration sample
+///
+/// A measurement paired with the XYZ values obtained with a reference
+/// instrument such as a spectrophotometer for the same light.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalibrationSample {
+    /// Sensor measurement.
+    pub measurement: AllChannelMeasurement,
+    /// Reference XYZ tristimulus values.
+    pub reference: Xyz,
+}
+
+/// Channels used by the calibration matrix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CalibrationChannels {
+    /// Red, green and blue channels (3×3 matrix).
+    Rgb,
+    /// Red, green, blue and clear channels (3×4 matrix).
+    Rgbc,
+}
+
+/// Calibration errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CalibrationError {
+    /// Fewer samples than matrix columns were provided.
+    NotEnoughSamples,
+    /// The samples do not determine the matrix, for example because all
+    /// of them have the same color.
+    Singular,
+}
+
+impl CalibrationMatrix {
+    /// Compute the best-fit matrix for a set of calibration samples.
+    ///
+    /// The matrix minimizes the sum of squared differences between the
+    /// converted measurements and the reference values. At least as many
+    /// samples as matrix columns are needed and they should cover a range
+    /// of colors. No heap allocation is needed.
+    pub fn solve(
+        samples: &[CalibrationSample],
+        channels: CalibrationChannels,
+    ) -> Result<Self, CalibrationError> {
+        let n = match channels {
+            CalibrationChannels::Rgb => 3,
+            CalibrationChannels::Rgbc => 4,
+        };
+        if samples.len() < n {
+            return Err(CalibrationError::NotEnoughSamples);
+        }
+        // Normal equations: (AᵀA) m = Aᵀy for each of X, Y and Z.
+        let mut ata = [[0.0_f64; 4]; 4];
+        let mut aty = [[0.0_f64; 3]; 4];
+        for sample in samples {
+            let a = channels_of(&sample.measurement);
+            let y = [
+                f64::from(sample.reference.x),
+                f64::from(sample.reference.y),
+                f64::from(sample.reference.z),
+            ];
+            for i in 0..n {
+                for j in 0..n {
+                    ata[i][j] += a[i] * a[j];
+                }
+                for (k, yk) in y.iter().enumerate() {
+                    aty[i][k] += a[i] * yk;
+                }
+            }
+        }
+        let solution = solve_linear(&mut ata, &mut aty, n)?;
+        let mut coefficients = [[0.0; 4]; 3];
+        for (k, row) in coefficients.iter_mut().enumerate() {
+            for (i, c) in row.iter_mut().enumerate().take(n) {
+                *c = solution[i][k] as f32;
+            }
+        }
+        Ok(CalibrationMatrix::from_rgbc(coefficients))
+    }
+
+    /// Residual error of a calibration sample.
+    ///
+    /// This is the Euclidean distance in XYZ between the converted
+    /// measurement and the reference values.
+    pub fn residual(&self, sample: &CalibrationSample) -> f32 {
+        let xyz = self.to_xyz(&sample.measurement);
+        let dx = xyz.x - sample.reference.x;
+        let dy = xyz.y - sample.reference.y;
+        let dz = xyz.z - sample.reference.z;
+        libm::sqrtf(dx * dx + dy * dy + dz * dz)
+    }
+
+    /// Compute the residual error of each calibration sample.
+    ///
+    /// The residual of `samples[i]` is stored in `residuals[i]`. Extra
+    /// samples or residual entries are ignored.
+    /// See [`residual()`](#method.residual).
+    pub fn residuals(&self, samples: &[CalibrationSample], residuals: &mut [f32]) {
+        for (sample, residual) in samples.iter().zip(residuals.iter_mut()) {
+            *residual = self.residual(sample);
+        }
+    }
+}
+
+fn channels_of(measurement: &AllChannelMeasurement) -> [f64; 4] {
+    [
+        f64::from(measurement.red),
+        f64::from(measurement.green),
+        f64::from(measurement.blue),
+        f64::from(measurement.clear),
+    ]
+}
+
+/// Solve `a x = b` for the first `n` rows with Gaussian elimination and
+/// partial pivoting. `b` has one column per right-hand side.
+fn solve_linear(
+    a: &mut [[f64; 4]; 4],
+    b: &mut [[f64; 3]; 4],
+    n: usize,
+) -> Result<[[f64; 3]; 4], CalibrationError> {
+    let scale = (0..n).map(|i| libm::fabs(a[i][i])).fold(0.0, f64::max);
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| libm::fabs(a[i][col]).total_cmp(&libm::fabs(a[j][col])))
+            .unwrap_or(col);
+        if libm::fabs(a[pivot][col]) <= scale * 1e-12 {
+            return Err(CalibrationError::Singular);
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let (pivot_a, pivot_b) = (a[col], b[col]);
+        for row in col + 1..n {
+            let factor = a[row][col] / pivot_a[col];
+            for (v, p) in a[row][col..n].iter_mut().zip(&pivot_a[col..n]) {
+                *v -= factor * p;
+            }
+            for (v, p) in b[row].iter_mut().zip(&pivot_b) {
+                *v -= factor * p;
+            }
+        }
+    }
+    let mut x = [[0.0; 3]; 4];
+    for row in (0..n).rev() {
+        for k in 0..3 {
+            let sum: f64 = (row + 1..n).map(|j| a[row][j] * x[j][k]).sum();
+            x[row][k] = (b[row][k] - sum) / a[row][row];
+        }
+    }
+    Ok(x)
+}
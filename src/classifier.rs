@@ -0,0 +1,160 @@
+//! Nearest-neighbour color classification.
+
+use crate::AllChannelMeasurement;
+
+/// Color classifier errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClassifierError {
+    /// The classifier already holds its maximum number of samples.
+    Full,
+    /// The measurement has no color information (red, green and blue are
+    /// all zero).
+    Dark,
+}
+
+/// Result of a classification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Classification<L> {
+    /// The measurement matches a trained class.
+    Known {
+        /// Label of the nearest sample.
+        label: L,
+        /// Chromaticity distance to the nearest sample.
+        distance: f32,
+        /// Confidence between 0.0 and 1.0.
+        ///
+        /// This compares the distance to the nearest sample of the class
+        /// with the distance to the nearest sample of any other class.
+        /// It is 1.0 if only one class is trained.
+        confidence: f32,
+    },
+    /// The measurement does not match any trained class, it has no color
+    /// information or no samples are trained.
+    Unknown,
+}
+
+/// Nearest-neighbour color classifier
+///
+/// Measurements are compared by their rg chromaticity
+/// (`r = R / (R + G + B)`, `g = G / (R + G + B)`), so that the
+/// classification does not depend on the intensity of the light.
+/// Up to `N` labelled samples are stored without heap allocation.
+/// Several samples can be trained with the same label.
+#[derive(Debug, Clone, Copy)]
+pub struct ColorClassifier<L, const N: usize> {
+    samples: [Option<(L, [f32; 2])>; N],
+    len: usize,
+    rejection_threshold: Option<f32>,
+}
+
+fn chromaticity(measurement: &AllChannelMeasurement) -> Option<[f32; 2]> {
+    let sum =
+        f32::from(measurement.red) + f32::from(measurement.green) + f32::from(measurement.blue);
+    if sum > 0.0 {
+        Some([
+            f32::from(measurement.red) / sum,
+            f32::from(measurement.green) / sum,
+        ])
+    } else {
+        None
+    }
+}
+
+fn distance(a: [f32; 2], b: [f32; 2]) -> f32 {
+    let dr = a[0] - b[0];
+    let dg = a[1] - b[1];
+    libm::sqrtf(dr * dr + dg * dg)
+}
+
+impl<L: Copy + PartialEq, const N: usize> Default for ColorClassifier<L, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L: Copy + PartialEq, const N: usize> ColorClassifier<L, N> {
+    /// Create an empty classifier without rejection threshold.
+    pub fn new() -> Self {
+        ColorClassifier {
+            samples: [None; N],
+            len: 0,
+            rejection_threshold: None,
+        }
+    }
+
+    /// Set the maximum chromaticity distance for a match.
+    ///
+    /// Measurements farther away from all samples are classified as
+    /// unknown. Typical values are in the range 0.01-0.05.
+    pub fn with_rejection_threshold(mut self, distance: f32) -> Self {
+        self.rejection_threshold = Some(distance);
+        self
+    }
+
+    /// Add a labelled sample.
+    pub fn add_sample(
+        &mut self,
+        label: L,
+        measurement: &AllChannelMeasurement,
+    ) -> Result<(), ClassifierError> {
+        if self.len == N {
+            return Err(ClassifierError::Full);
+        }
+        let rg = chromaticity(measurement).ok_or(ClassifierError::Dark)?;
+        self.samples[self.len] = Some((label, rg));
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Number of trained samples.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no samples are trained.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all samples.
+    pub fn clear(&mut self) {
+        self.samples = [None; N];
+        self.len = 0;
+    }
+
+    /// Classify a measurement.
+    pub fn classify(&self, measurement: &AllChannelMeasurement) -> Classification<L> {
+        let rg = match chromaticity(measurement) {
+            Some(rg) => rg,
+            None => return Classification::Unknown,
+        };
+        let samples = self.samples.iter().flatten();
+        let nearest = samples
+            .clone()
+            .map(|(label, sample)| (*label, distance(rg, *sample)))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let (label, nearest_distance) = match nearest {
+            Some(nearest) => nearest,
+            None => return Classification::Unknown,
+        };
+        if let Some(threshold) = self.rejection_threshold {
+            if nearest_distance > threshold {
+                return Classification::Unknown;
+            }
+        }
+        let other_distance = samples
+            .filter(|(other, _)| *other != label)
+            .map(|(_, sample)| distance(rg, *sample))
+            .min_by(|a, b| a.total_cmp(b));
+        let confidence = match other_distance {
+            Some(other) if other > 0.0 => 1.0 - nearest_distance / other,
+            Some(_) => 0.0,
+            None => 1.0,
+        };
+        Classification::Known {
+            label,
+            distance: nearest_distance,
+            confidence,
+        }
+    }
+}
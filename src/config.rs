@@ -0,0 +1,197 @@
+//! Device configuration applied at once.
+
+use crate::registers::{self, Atime, Control, Enable, Pers, TypedRegister, Wtime};
+use crate::shadow::SHADOW_LEN;
+use crate::{EnableFlags, Error, Register, RgbCGain, RgbCInterruptPersistence};
+
+/// Complete device configuration
+///
+/// This can be written to the device at once with
+/// [`Tcs3472::apply_config()`](crate::Tcs3472::apply_config) and read back with
+/// [`Tcs3472::read_config()`](crate::Tcs3472::read_config).
+/// Create it with [`Config::builder()`]. The default corresponds to the
+/// device power-on state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Config {
+    pub(crate) enable: EnableFlags,
+    pub(crate) gain: RgbCGain,
+    pub(crate) integration_cycles: u16,
+    pub(crate) wait_cycles: u16,
+    pub(crate) wait_long: bool,
+    pub(crate) low_threshold: u16,
+    pub(crate) high_threshold: u16,
+    pub(crate) persistence: RgbCInterruptPersistence,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            enable: EnableFlags::default(),
+            gain: RgbCGain::_1x,
+            integration_cycles: 1,
+            wait_cycles: 1,
+            wait_long: false,
+            low_threshold: 0,
+            high_threshold: 0,
+            persistence: RgbCInterruptPersistence::Every,
+        }
+    }
+}
+
+impl Config {
+    /// Create a builder starting from the device power-on configuration.
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder {
+            config: Config::default(),
+        }
+    }
+
+    /// Decode the configuration from the registers ENABLE to CONTROL.
+    pub(crate) fn from_registers(data: &[u8; SHADOW_LEN]) -> Option<Self> {
+        fn decode<R: TypedRegister>(data: &[u8; SHADOW_LEN]) -> Option<R> {
+            R::decode(data[usize::from(R::ADDRESS)])
+        }
+        let threshold = |register: u8| {
+            let i = usize::from(register);
+            u16::from_le_bytes([data[i], data[i + 1]])
+        };
+        Some(Config {
+            enable: decode::<Enable>(data)?,
+            gain: decode::<Control>(data)?.gain,
+            integration_cycles: decode::<Atime>(data)?.cycles(),
+            wait_cycles: decode::<Wtime>(data)?.cycles(),
+            wait_long: decode::<registers::ConfigRegister>(data)?.wait_long,
+            low_threshold: threshold(Register::AILTL),
+            high_threshold: threshold(Register::AIHTL),
+            persistence: decode::<Pers>(data)?.persistence,
+        })
+    }
+
+    /// Enable register flags.
+    pub fn enable_flags(&self) -> EnableFlags {
+        self.enable
+    }
+
+    /// RGB converter gain.
+    pub fn gain(&self) -> RgbCGain {
+        self.gain
+    }
+
+    /// Number of integration cycles (1-256).
+    pub fn integration_cycles(&self) -> u16 {
+        self.integration_cycles
+    }
+
+    /// Number of wait time cycles (1-256).
+    pub fn wait_cycles(&self) -> u16 {
+        self.wait_cycles
+    }
+
+    /// Whether the *wait long* setting is enabled.
+    pub fn wait_long(&self) -> bool {
+        self.wait_long
+    }
+
+    /// RGB converter interrupt clear channel low threshold.
+    pub fn rgbc_interrupt_low_threshold(&self) -> u16 {
+        self.low_threshold
+    }
+
+    /// RGB converter interrupt clear channel high threshold.
+    pub fn rgbc_interrupt_high_threshold(&self) -> u16 {
+        self.high_threshold
+    }
+
+    /// RGB converter interrupt persistence.
+    pub fn rgbc_interrupt_persistence(&self) -> RgbCInterruptPersistence {
+        self.persistence
+    }
+}
+
+impl From<Config> for ConfigBuilder {
+    fn from(config: Config) -> Self {
+        ConfigBuilder { config }
+    }
+}
+
+/// Invalid configuration error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConfigError {
+    /// The number of integration cycles is out of range (1-256).
+    IntegrationCycles,
+    /// The number of wait time cycles is out of range (1-256).
+    WaitCycles,
+}
+
+impl<E> From<ConfigError> for Error<E> {
+    fn from(_: ConfigError) -> Self {
+        Error::InvalidInputData
+    }
+}
+
+/// Builder for [`Config`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    /// Set the enable register flags.
+    pub fn enable_flags(mut self, enable: EnableFlags) -> Self {
+        self.config.enable = enable;
+        self
+    }
+
+    /// Set the RGB converter gain.
+    pub fn gain(mut self, gain: RgbCGain) -> Self {
+        self.config.gain = gain;
+        self
+    }
+
+    /// Set the number of integration cycles (1-256).
+    pub fn integration_cycles(mut self, cycles: u16) -> Self {
+        self.config.integration_cycles = cycles;
+        self
+    }
+
+    /// Set the number of wait time cycles (1-256).
+    pub fn wait_cycles(mut self, cycles: u16) -> Self {
+        self.config.wait_cycles = cycles;
+        self
+    }
+
+    /// Set whether the *wait long* setting is enabled.
+    pub fn wait_long(mut self, enabled: bool) -> Self {
+        self.config.wait_long = enabled;
+        self
+    }
+
+    /// Set the RGB converter interrupt clear channel low threshold.
+    pub fn rgbc_interrupt_low_threshold(mut self, threshold: u16) -> Self {
+        self.config.low_threshold = threshold;
+        self
+    }
+
+    /// Set the RGB converter interrupt clear channel high threshold.
+    pub fn rgbc_interrupt_high_threshold(mut self, threshold: u16) -> Self {
+        self.config.high_threshold = threshold;
+        self
+    }
+
+    /// Set the RGB converter interrupt persistence.
+    pub fn rgbc_interrupt_persistence(mut self, persistence: RgbCInterruptPersistence) -> Self {
+        self.config.persistence = persistence;
+        self
+    }
+
+    /// Validate the settings and create the configuration.
+    pub fn build(self) -> Result<Config, ConfigError> {
+        if !(1..=256).contains(&self.config.integration_cycles) {
+            return Err(ConfigError::IntegrationCycles);
+        }
+        if !(1..=256).contains(&self.config.wait_cycles) {
+            return Err(ConfigError::WaitCycles);
+        }
+        Ok(self.config)
+    }
+}
@@ -1,18 +1,22 @@
+use crate::interface::CYCLE_TIME_US;
+use crate::registers::{
+    self, Atime, Control, Enable, Pers, TypedRegister, WritableRegister, Wtime,
+};
+use crate::schedule::{cycles_for_us, wait_for_us, wait_time_us};
+use crate::shadow::SHADOW_LEN;
+#[cfg(feature = "async")]
+use crate::Tcs3472Async;
 use crate::{
-    BitFlags, Error, Register, RgbCGain, RgbCInterruptPersistence, Tcs3472, DEVICE_ADDRESS,
+    BitFlags, Config, EnableFlags, Error, Register, RgbCGain, RgbCInterruptPersistence,
+    RgbCInterruptTrigger, Tcs3472,
 };
-#[cfg(not(feature = "async"))]
 use embedded_hal::i2c::I2c;
 #[cfg(feature = "async")]
 use embedded_hal_async::i2c::I2c as AsyncI2c;
 
 #[maybe_async_cfg::maybe(
-    sync(
-        cfg(not(feature = "async")),
-        self = "Tcs3472",
-        idents(AsyncI2c(sync = "I2c"))
-    ),
-    async(feature = "async", keep_self)
+    sync(self = "Tcs3472", idents(AsyncI2c(sync = "I2c"))),
+    async(feature = "async", self = "Tcs3472Async")
 )]
 impl<I2C, E> Tcs3472<I2C>
 where
@@ -22,56 +26,63 @@ where
     ///
     /// The device goes to idle state.
     pub async fn enable(&mut self) -> Result<(), Error<E>> {
-        let enable = self.enable;
+        let enable = self.shadow.enable();
         self.write_enable(enable | BitFlags::POWER_ON).await
     }
 
     /// Disable the device (sleep).
     pub async fn disable(&mut self) -> Result<(), Error<E>> {
-        let enable = self.enable;
+        let enable = self.shadow.enable();
         self.write_enable(enable & !BitFlags::POWER_ON).await
     }
 
     /// Enable the RGB converter.
     pub async fn enable_rgbc(&mut self) -> Result<(), Error<E>> {
-        let enable = self.enable;
+        let enable = self.shadow.enable();
         self.write_enable(enable | BitFlags::RGBC_EN).await
     }
 
     /// Disable the RGB converter.
     pub async fn disable_rgbc(&mut self) -> Result<(), Error<E>> {
-        let enable = self.enable;
+        let enable = self.shadow.enable();
         self.write_enable(enable & !BitFlags::RGBC_EN).await
     }
 
     /// Enable the RGB converter interrupt generation.
     pub async fn enable_rgbc_interrupts(&mut self) -> Result<(), Error<E>> {
-        let enable = self.enable;
+        let enable = self.shadow.enable();
         self.write_enable(enable | BitFlags::RGBC_INT_EN).await
     }
 
     /// Disable the RGB converter interrupt generation.
     pub async fn disable_rgbc_interrupts(&mut self) -> Result<(), Error<E>> {
-        let enable = self.enable;
+        let enable = self.shadow.enable();
         self.write_enable(enable & !BitFlags::RGBC_INT_EN).await
     }
 
     /// Enable the wait feature (wait timer).
     pub async fn enable_wait(&mut self) -> Result<(), Error<E>> {
-        let enable = self.enable;
+        let enable = self.shadow.enable();
         self.write_enable(enable | BitFlags::WAIT_EN).await
     }
 
     /// Disable the wait feature (wait timer).
     pub async fn disable_wait(&mut self) -> Result<(), Error<E>> {
-        let enable = self.enable;
+        let enable = self.shadow.enable();
         self.write_enable(enable & !BitFlags::WAIT_EN).await
     }
 
-    async fn write_enable(&mut self, enable: u8) -> Result<(), Error<E>> {
-        self.write_register(Register::ENABLE, enable).await?;
-        self.enable = enable;
-        Ok(())
+    /// Read the enable register flags from the device.
+    ///
+    /// The flags are also stored in the driver so that subsequent calls to
+    /// the enable/disable methods preserve the settings found in the device,
+    /// e.g. after an MCU reset.
+    pub async fn read_enable_flags(&mut self) -> Result<EnableFlags, Error<E>> {
+        self.read_typed_register::<Enable>().await
+    }
+
+    pub(crate) async fn write_enable(&mut self, enable: u8) -> Result<(), Error<E>> {
+        self.write_register(Register::ENABLE, enable).await
     }
 
     /// Set the number of wait time cycles  (1-256).
@@ -84,14 +95,20 @@ where
     ///   `number_of_cycles * 0.029s`.
     ///
     /// See [`enable_wait_long()`](#method.enable_wait_long) and
-    ///  [`disable_wait_long()`](#method.disable_wait_long).
+    ///  [`disable_wait_long()`](#method.disable_wait_long), or
+    /// [`set_wait_time_us()`](#method.set_wait_time_us) to set the wait time
+    /// as a duration.
     pub async fn set_wait_cycles(&mut self, cycles: u16) -> Result<(), Error<E>> {
-        if cycles > 256 || cycles == 0 {
-            return Err(Error::InvalidInputData);
-        }
-        // the value is stored as a two's complement
-        self.write_register(Register::WTIME, (256_u16 - cycles) as u8)
-            .await
+        let wtime = Wtime::new(cycles).ok_or(Error::InvalidInputData)?;
+        self.write_typed_register(wtime).await
+    }
+
+    /// Read the number of wait time cycles (1-256) from the device.
+    ///
+    /// See [`set_wait_cycles()`](#method.set_wait_cycles).
+    pub async fn read_wait_cycles(&mut self) -> Result<u16, Error<E>> {
+        let wtime = self.read_typed_register::<Wtime>().await?;
+        Ok(wtime.cycles())
     }
 
     /// Enable the *wait long* setting.
@@ -99,7 +116,8 @@ where
     /// The wait time configured with `set_wait_cycles()` is increased by a
     /// factor of 12. See [`set_wait_cycles()`](#method.set_wait_cycles).
     pub async fn enable_wait_long(&mut self) -> Result<(), Error<E>> {
-        self.write_register(Register::CONFIG, BitFlags::WLONG).await
+        self.write_typed_register(registers::ConfigRegister { wait_long: true })
+            .await
     }
 
     /// Disable the *wait long* setting.
@@ -107,30 +125,92 @@ where
     /// The wait time configured with `set_wait_cycles()` is used without
     /// multiplication factor. See [`set_wait_cycles()`](#method.set_wait_cycles).
     pub async fn disable_wait_long(&mut self) -> Result<(), Error<E>> {
-        self.write_register(Register::CONFIG, 0).await
+        self.write_typed_register(registers::ConfigRegister { wait_long: false })
+            .await
+    }
+
+    /// Read whether the *wait long* setting is enabled from the device.
+    #[allow(clippy::wrong_self_convention)]
+    pub async fn is_wait_long_enabled(&mut self) -> Result<bool, Error<E>> {
+        let config = self
+            .read_typed_register::<registers::ConfigRegister>()
+            .await?;
+        Ok(config.wait_long)
+    }
+
+    /// Set the wait time in microseconds.
+    ///
+    /// The wait time is rounded to the nearest achievable value, using the
+    /// *wait long* setting only if it gets closer. This sets the number of
+    /// wait cycles and the *wait long* setting and returns the actual wait
+    /// time in microseconds. Returns `Error::InvalidInputData` if the wait
+    /// time is out of range (2.4ms-7.3728s).
+    ///
+    /// The wait feature must be enabled separately.
+    /// See [`enable_wait()`](#method.enable_wait).
+    pub async fn set_wait_time_us(&mut self, time_us: u32) -> Result<u32, Error<E>> {
+        let (cycles, wait_long) = wait_for_us(time_us).ok_or(Error::InvalidInputData)?;
+        self.set_wait_cycles(cycles).await?;
+        self.write_typed_register(registers::ConfigRegister { wait_long })
+            .await?;
+        Ok(wait_time_us(cycles, wait_long))
+    }
+
+    /// Read the wait time in microseconds from the device.
+    ///
+    /// This takes the number of wait cycles and the *wait long* setting
+    /// into account.
+    pub async fn read_wait_time_us(&mut self) -> Result<u32, Error<E>> {
+        let cycles = self.read_wait_cycles().await?;
+        let wait_long = self.is_wait_long_enabled().await?;
+        Ok(wait_time_us(cycles, wait_long))
     }
 
     /// Set the RGB converter gain.
     pub async fn set_rgbc_gain(&mut self, gain: RgbCGain) -> Result<(), Error<E>> {
-        // Register field: AGAIN
-        match gain {
-            RgbCGain::_1x => self.write_register(Register::CONTROL, 0).await,
-            RgbCGain::_4x => self.write_register(Register::CONTROL, 1).await,
-            RgbCGain::_16x => self.write_register(Register::CONTROL, 2).await,
-            RgbCGain::_60x => self.write_register(Register::CONTROL, 3).await,
-        }
+        self.write_typed_register(Control { gain }).await
+    }
+
+    /// Read the RGB converter gain from the device.
+    pub async fn read_rgbc_gain(&mut self) -> Result<RgbCGain, Error<E>> {
+        let control = self.read_typed_register::<Control>().await?;
+        Ok(control.gain)
     }
 
     /// Set the number of integration cycles (1-256).
     ///
     /// The actual integration time corresponds to: `number_of_cycles * 2.4ms`.
+    /// See [`set_integration_time_us()`](#method.set_integration_time_us)
+    /// to set it as a duration.
     pub async fn set_integration_cycles(&mut self, cycles: u16) -> Result<(), Error<E>> {
-        if cycles > 256 || cycles == 0 {
-            return Err(Error::InvalidInputData);
-        }
-        // the value is stored as a two's complement
-        self.write_register(Register::ATIME, (256_u16 - cycles) as u8)
-            .await
+        let atime = Atime::new(cycles).ok_or(Error::InvalidInputData)?;
+        self.write_typed_register(atime).await
+    }
+
+    /// Read the number of integration cycles (1-256) from the device.
+    ///
+    /// The value is also stored in the driver for the saturation detection.
+    pub async fn read_integration_cycles(&mut self) -> Result<u16, Error<E>> {
+        let atime = self.read_typed_register::<Atime>().await?;
+        Ok(atime.cycles())
+    }
+
+    /// Set the integration time in microseconds.
+    ///
+    /// The integration time is rounded to the nearest number of cycles.
+    /// This returns the actual integration time in microseconds.
+    /// Returns `Error::InvalidInputData` if the integration time is out of
+    /// range (2.4ms-614.4ms).
+    pub async fn set_integration_time_us(&mut self, time_us: u32) -> Result<u32, Error<E>> {
+        let cycles = cycles_for_us(time_us, CYCLE_TIME_US).ok_or(Error::InvalidInputData)?;
+        self.set_integration_cycles(cycles).await?;
+        Ok(u32::from(cycles) * CYCLE_TIME_US)
+    }
+
+    /// Read the integration time in microseconds from the device.
+    pub async fn read_integration_time_us(&mut self) -> Result<u32, Error<E>> {
+        let cycles = self.read_integration_cycles().await?;
+        Ok(u32::from(cycles) * CYCLE_TIME_US)
     }
 
     /// Set the RGB converter interrupt clear channel low threshold.
@@ -138,7 +218,7 @@ where
         &mut self,
         threshold: u16,
     ) -> Result<(), Error<E>> {
-        self.write_registers(Register::AILTL, threshold as u8, (threshold >> 8) as u8)
+        self.write_registers(Register::AILTL, &threshold.to_le_bytes())
             .await
     }
 
@@ -147,10 +227,26 @@ where
         &mut self,
         threshold: u16,
     ) -> Result<(), Error<E>> {
-        self.write_registers(Register::AIHTL, threshold as u8, (threshold >> 8) as u8)
+        self.write_registers(Register::AIHTL, &threshold.to_le_bytes())
             .await
     }
 
+    /// Read the RGB converter interrupt clear channel low threshold from the device.
+    pub async fn read_rgbc_interrupt_low_threshold(&mut self) -> Result<u16, Error<E>> {
+        self.read_threshold(Register::AILTL).await
+    }
+
+    /// Read the RGB converter interrupt clear channel high threshold from the device.
+    pub async fn read_rgbc_interrupt_high_threshold(&mut self) -> Result<u16, Error<E>> {
+        self.read_threshold(Register::AIHTL).await
+    }
+
+    async fn read_threshold(&mut self, first_register: u8) -> Result<u16, Error<E>> {
+        let mut data = [0; 2];
+        self.read_registers(first_register, &mut data).await?;
+        Ok(u16::from(data[1]) << 8 | u16::from(data[0]))
+    }
+
     /// Set the RGB converter interrupt persistence.
     ///
     /// This controls the RGB converter interrupt generation rate.
@@ -158,45 +254,139 @@ where
         &mut self,
         persistence: RgbCInterruptPersistence,
     ) -> Result<(), Error<I2C::Error>> {
-        use crate::RgbCInterruptPersistence as IP;
-        match persistence {
-            IP::Every => self.write_register(Register::APERS, 0).await,
-            IP::_1 => self.write_register(Register::APERS, 1).await,
-            IP::_2 => self.write_register(Register::APERS, 2).await,
-            IP::_3 => self.write_register(Register::APERS, 3).await,
-            IP::_5 => self.write_register(Register::APERS, 4).await,
-            IP::_10 => self.write_register(Register::APERS, 5).await,
-            IP::_15 => self.write_register(Register::APERS, 6).await,
-            IP::_20 => self.write_register(Register::APERS, 7).await,
-            IP::_25 => self.write_register(Register::APERS, 8).await,
-            IP::_30 => self.write_register(Register::APERS, 9).await,
-            IP::_35 => self.write_register(Register::APERS, 10).await,
-            IP::_40 => self.write_register(Register::APERS, 11).await,
-            IP::_45 => self.write_register(Register::APERS, 12).await,
-            IP::_50 => self.write_register(Register::APERS, 13).await,
-            IP::_55 => self.write_register(Register::APERS, 14).await,
-            IP::_60 => self.write_register(Register::APERS, 15).await,
+        self.write_typed_register(Pers { persistence }).await
+    }
+
+    /// Read the RGB converter interrupt persistence from the device.
+    pub async fn read_rgbc_interrupt_persistence(
+        &mut self,
+    ) -> Result<RgbCInterruptPersistence, Error<E>> {
+        let apers = self.read_typed_register::<Pers>().await?;
+        Ok(apers.persistence)
+    }
+
+    /// Configure the RGB converter interrupt generation.
+    ///
+    /// This sets the persistence (and thresholds), clears any pending
+    /// interrupt and enables the RGB converter interrupt generation.
+    pub async fn configure_rgbc_interrupt(
+        &mut self,
+        trigger: RgbCInterruptTrigger,
+    ) -> Result<(), Error<E>> {
+        match trigger {
+            RgbCInterruptTrigger::EveryCycle => {
+                self.set_rgbc_interrupt_persistence(RgbCInterruptPersistence::Every)
+                    .await?;
+            }
+            RgbCInterruptTrigger::Thresholds {
+                low,
+                high,
+                persistence,
+            } => {
+                self.set_rgbc_interrupt_low_threshold(low).await?;
+                self.set_rgbc_interrupt_high_threshold(high).await?;
+                self.set_rgbc_interrupt_persistence(persistence).await?;
+            }
         }
+        self.clear_rgbc_interrupt().await?;
+        self.enable_rgbc_interrupts().await
+    }
+
+    /// Apply a complete configuration.
+    ///
+    /// Contiguous registers are written together using the auto-increment
+    /// command to minimize the bus traffic. The enable register is written
+    /// last so that the RGB converter starts with the new settings.
+    ///
+    /// Note that the device needs a warm-up time of 2.4ms after being
+    /// powered on before the RGB converter can be enabled.
+    pub async fn apply_config(&mut self, config: &Config) -> Result<(), Error<E>> {
+        let [ailtl, ailth] = config.low_threshold.to_le_bytes();
+        let [aihtl, aihth] = config.high_threshold.to_le_bytes();
+        let atime = Atime::new(config.integration_cycles).ok_or(Error::InvalidInputData)?;
+        let wtime = Wtime::new(config.wait_cycles).ok_or(Error::InvalidInputData)?;
+        let pers = Pers {
+            persistence: config.persistence,
+        };
+        let wlong = registers::ConfigRegister {
+            wait_long: config.wait_long,
+        };
+        self.write_typed_register(atime).await?;
+        let burst = [wtime.encode(), ailtl, ailth, aihtl, aihth];
+        self.write_registers(Register::WTIME, &burst).await?;
+        self.write_registers(Register::APERS, &[pers.encode(), wlong.encode()])
+            .await?;
+        self.write_typed_register(Control { gain: config.gain })
+            .await?;
+        self.write_typed_register(config.enable).await
     }
 
-    async fn write_register(&mut self, register: u8, value: u8) -> Result<(), Error<E>> {
+    /// Read the complete configuration from the device.
+    ///
+    /// All configuration registers are read at once and stored in the
+    /// shadow copy.
+    pub async fn read_config(&mut self) -> Result<Config, Error<E>> {
+        let mut data = [0; SHADOW_LEN];
+        self.read_registers(Register::ENABLE, &mut data).await?;
+        Config::from_registers(&data).ok_or(Error::InvalidRegisterValue)
+    }
+
+    /// Write a typed register.
+    ///
+    /// See the [`registers`] module.
+    pub async fn write_typed_register<R: WritableRegister>(
+        &mut self,
+        value: R,
+    ) -> Result<(), Error<E>> {
+        self.write_register(R::ADDRESS, value.encode()).await
+    }
+
+    /// Write a raw register value.
+    ///
+    /// This gives access to anything the rest of the API does not cover.
+    /// Writes to the configuration registers update the shadow copy and
+    /// are skipped if the register is known to hold the value already.
+    /// Returns `Error::InvalidInputData` if the register address is
+    /// beyond 0x1F.
+    pub async fn write_register(&mut self, register: u8, value: u8) -> Result<(), Error<E>> {
+        if register > BitFlags::CMD_ADDR {
+            return Err(Error::InvalidInputData);
+        }
+        if self.shadow.contains(register, &[value]) {
+            return Ok(());
+        }
         let command = BitFlags::CMD | register;
-        self.i2c
-            .write(DEVICE_ADDRESS, &[command, value])
-            .await
-            .map_err(Error::I2C)
+        let result = self.i2c.write(self.address, &[command, value]).await;
+        self.update_shadow(register, &[value], result)
     }
 
-    async fn write_registers(
+    async fn write_registers(&mut self, first_register: u8, values: &[u8]) -> Result<(), Error<E>> {
+        if self.shadow.contains(first_register, values) {
+            return Ok(());
+        }
+        let mut data = [0; 6];
+        data[0] = BitFlags::CMD | BitFlags::CMD_AUTO_INC | first_register;
+        data[1..=values.len()].copy_from_slice(values);
+        let result = self.i2c.write(self.address, &data[..=values.len()]).await;
+        self.update_shadow(first_register, values, result)
+    }
+
+    fn update_shadow(
         &mut self,
-        register: u8,
-        value0: u8,
-        value1: u8,
+        first_register: u8,
+        values: &[u8],
+        result: Result<(), E>,
     ) -> Result<(), Error<E>> {
-        let command = BitFlags::CMD | BitFlags::CMD_AUTO_INC | register;
-        self.i2c
-            .write(DEVICE_ADDRESS, &[command, value0, value1])
-            .await
-            .map_err(Error::I2C)
+        match result {
+            Ok(()) => {
+                self.shadow.store(first_register, values);
+                Ok(())
+            }
+            Err(e) => {
+                // the device may or may not hold the new values
+                self.shadow.invalidate(first_register, values.len());
+                Err(Error::I2C(e))
+            }
+        }
     }
 }
@@ -1,4 +1,18 @@
 pub(crate) const DEVICE_ADDRESS: u8 = 0x29;
+pub(crate) const DEVICE_ADDRESS_ALT: u8 = 0x39;
+
+/// Duration of an integration or wait cycle and of the power-on warm-up.
+pub(crate) const CYCLE_TIME_US: u32 = 2400;
+
+/// Factor of the wait time if the *wait long* setting is enabled.
+pub(crate) const WAIT_LONG_FACTOR: u32 = 12;
+
+pub(crate) struct DeviceId;
+
+impl DeviceId {
+    pub(crate) const TCS34721_5: u8 = 0x44;
+    pub(crate) const TCS34723_7: u8 = 0x4D;
+}
 
 pub(crate) struct Register;
 
@@ -24,10 +38,16 @@ pub(crate) struct BitFlags;
 impl BitFlags {
     pub(crate) const CMD: u8 = 0b1000_0000;
     pub(crate) const CMD_AUTO_INC: u8 = 0b0010_0000;
+    pub(crate) const CMD_ADDR: u8 = 0b0001_1111;
+    pub(crate) const CMD_SPECIAL_FN: u8 = 0b0110_0000;
+    pub(crate) const RGBC_INT_CLEAR: u8 = 0b0000_0110;
     pub(crate) const POWER_ON: u8 = 0b0000_0001; // PON
     pub(crate) const RGBC_EN: u8 = 0b0000_0010; // AEN
     pub(crate) const WAIT_EN: u8 = 0b0000_1000; // WEN
     pub(crate) const RGBC_INT_EN: u8 = 0b0001_0000; // AIEN
     pub(crate) const RGBC_VALID: u8 = 0b0000_0001; // AVALID
+    pub(crate) const RGBC_INT: u8 = 0b0001_0000; // AINT
     pub(crate) const WLONG: u8 = 0b0000_0010;
+    pub(crate) const GAIN_MASK: u8 = 0b0000_0011; // AGAIN
+    pub(crate) const PERSISTENCE_MASK: u8 = 0b0000_1111; // APERS
 }
@@ -0,0 +1,49 @@
+//! IR component estimation and removal.
+
+use crate::AllChannelMeasurement;
+
+/// Measurement with the IR component removed
+///
+/// The compensated measurement can be passed on to any further
+/// processing such as the XYZ or RGB conversions.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IrCompensated {
+    /// Estimated IR component in counts.
+    pub ir: u16,
+    /// Measurement with the IR component subtracted from all channels.
+    pub measurement: AllChannelMeasurement,
+}
+
+impl AllChannelMeasurement {
+    /// Estimate the IR component as in the ams design note DN40.
+    ///
+    /// The estimate is `(R + G + B - C) / 2`, clamped to zero if noise or
+    /// saturation makes it negative.
+    pub fn ir_estimate(&self) -> u16 {
+        let sum = u32::from(self.red) + u32::from(self.green) + u32::from(self.blue);
+        let ir = sum.saturating_sub(u32::from(self.clear)) / 2;
+        u16::try_from(ir).unwrap_or(u16::MAX)
+    }
+
+    /// Estimate the IR component and subtract it from all channels.
+    ///
+    /// Channels smaller than the IR estimate are clamped to zero.
+    pub fn ir_compensated(&self) -> IrCompensated {
+        let ir = self.ir_estimate();
+        IrCompensated {
+            ir,
+            measurement: AllChannelMeasurement {
+                red: self.red.saturating_sub(ir),
+                green: self.green.saturating_sub(ir),
+                blue: self.blue.saturating_sub(ir),
+                clear: self.clear.saturating_sub(ir),
+            },
+        }
+    }
+}
+
+impl From<AllChannelMeasurement> for IrCompensated {
+    fn from(measurement: AllChannelMeasurement) -> Self {
+        measurement.ir_compensated()
+    }
+}
@@ -0,0 +1,287 @@
+//! CIELAB and CIELUV conversion and color difference metrics.
+
+use crate::{AllChannelMeasurement, CalibrationMatrix, Xyz};
+
+/// CIE standard illuminant used as reference white
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Illuminant {
+    /// Average daylight, 6504 K (default).
+    #[default]
+    D65,
+    /// Horizon daylight, 5003 K. Common in the graphic arts.
+    D50,
+    /// Incandescent light, 2856 K.
+    A,
+}
+
+impl Illuminant {
+    /// White point for the CIE 1931 2° observer normalized to Y = 1.
+    pub fn white_point(self) -> Xyz {
+        let (x, z) = match self {
+            Illuminant::D65 => (0.950_47, 1.088_83),
+            Illuminant::D50 => (0.964_22, 0.825_21),
+            Illuminant::A => (1.098_50, 0.355_85),
+        };
+        Xyz { x, y: 1.0, z }
+    }
+}
+
+/// CIE 1976 L\*a\*b\* color
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lab {
+    /// Lightness L\* (0-100).
+    pub l: f32,
+    /// Green-red axis a\*.
+    pub a: f32,
+    /// Blue-yellow axis b\*.
+    pub b: f32,
+}
+
+/// CIE 1976 L\*u\*v\* color
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Luv {
+    /// Lightness L\* (0-100).
+    pub l: f32,
+    /// u\* coordinate.
+    pub u: f32,
+    /// v\* coordinate.
+    pub v: f32,
+}
+
+const EPSILON: f32 = 216.0 / 24389.0;
+const KAPPA: f32 = 24389.0 / 27.0;
+
+fn lab_f(t: f32) -> f32 {
+    if t > EPSILON {
+        libm::cbrtf(t)
+    } else {
+        (KAPPA * t + 16.0) / 116.0
+    }
+}
+
+fn lightness(relative_luminance: f32) -> f32 {
+    116.0 * lab_f(relative_luminance) - 16.0
+}
+
+fn uv_prime(xyz: &Xyz) -> (f32, f32) {
+    let denominator = xyz.x + 15.0 * xyz.y + 3.0 * xyz.z;
+    if denominator > 0.0 {
+        (4.0 * xyz.x / denominator, 9.0 * xyz.y / denominator)
+    } else {
+        (0.0, 0.0)
+    }
+}
+
+impl Xyz {
+    /// Convert into CIELAB relative to a reference white.
+    ///
+    /// The reference white must have the same scale as these values.
+    pub fn to_lab(&self, white: &Xyz) -> Lab {
+        let fx = lab_f(self.x / white.x);
+        let fy = lab_f(self.y / white.y);
+        let fz = lab_f(self.z / white.z);
+        Lab {
+            l: 116.0 * fy - 16.0,
+            a: 500.0 * (fx - fy),
+            b: 200.0 * (fy - fz),
+        }
+    }
+
+    /// Convert into CIELUV relative to a reference white.
+    ///
+    /// The reference white must have the same scale as these values.
+    pub fn to_luv(&self, white: &Xyz) -> Luv {
+        let l = lightness(self.y / white.y);
+        let (u, v) = uv_prime(self);
+        let (un, vn) = uv_prime(white);
+        if u == 0.0 && v == 0.0 {
+            return Luv { l, u: 0.0, v: 0.0 };
+        }
+        Luv {
+            l,
+            u: 13.0 * l * (u - un),
+            v: 13.0 * l * (v - vn),
+        }
+    }
+}
+
+impl Lab {
+    /// Chroma C\*.
+    pub fn chroma(&self) -> f32 {
+        libm::sqrtf(self.a * self.a + self.b * self.b)
+    }
+
+    /// CIE76 color difference (Euclidean distance).
+    pub fn delta_e76(&self, other: &Lab) -> f32 {
+        let dl = self.l - other.l;
+        let da = self.a - other.a;
+        let db = self.b - other.b;
+        libm::sqrtf(dl * dl + da * da + db * db)
+    }
+
+    /// CIE94 color difference of this sample to a reference.
+    ///
+    /// Uses the graphic arts weighting factors. The metric is not
+    /// symmetric: the chroma of the reference determines the weighting.
+    pub fn delta_e94(&self, reference: &Lab) -> f32 {
+        let c1 = reference.chroma();
+        let c2 = self.chroma();
+        let dl = reference.l - self.l;
+        let dc = c1 - c2;
+        let da = reference.a - self.a;
+        let db = reference.b - self.b;
+        let dh2 = (da * da + db * db - dc * dc).max(0.0);
+        let sc = 1.0 + 0.045 * c1;
+        let sh = 1.0 + 0.015 * c1;
+        libm::sqrtf(dl * dl + (dc / sc) * (dc / sc) + dh2 / (sh * sh))
+    }
+
+    /// CIEDE2000 color difference.
+    ///
+    /// Uses unity parametric weighting factors.
+    pub fn delta_e2000(&self, other: &Lab) -> f32 {
+        delta_e2000(self, other) as f32
+    }
+}
+
+fn chroma_weight(c: f64) -> f64 {
+    let c7 = libm::pow(c, 7.0);
+    libm::sqrt(c7 / (c7 + libm::pow(25.0, 7.0)))
+}
+
+fn hue_degrees(a: f64, b: f64) -> f64 {
+    if a == 0.0 && b == 0.0 {
+        0.0
+    } else {
+        let h = libm::atan2(b, a).to_degrees();
+        if h < 0.0 {
+            h + 360.0
+        } else {
+            h
+        }
+    }
+}
+
+// Computed in double precision to match the published reference data.
+fn delta_e2000(lab1: &Lab, lab2: &Lab) -> f64 {
+    let (l1, a1, b1) = (f64::from(lab1.l), f64::from(lab1.a), f64::from(lab1.b));
+    let (l2, a2, b2) = (f64::from(lab2.l), f64::from(lab2.a), f64::from(lab2.b));
+    let c_mean = (libm::hypot(a1, b1) + libm::hypot(a2, b2)) / 2.0;
+    let g = 0.5 * (1.0 - chroma_weight(c_mean));
+    let a1 = (1.0 + g) * a1;
+    let a2 = (1.0 + g) * a2;
+    let c1 = libm::hypot(a1, b1);
+    let c2 = libm::hypot(a2, b2);
+    let h1 = hue_degrees(a1, b1);
+    let h2 = hue_degrees(a2, b2);
+
+    let dl = l2 - l1;
+    let dc = c2 - c1;
+    let dh = if c1 * c2 == 0.0 {
+        0.0
+    } else if h2 - h1 > 180.0 {
+        h2 - h1 - 360.0
+    } else if h2 - h1 < -180.0 {
+        h2 - h1 + 360.0
+    } else {
+        h2 - h1
+    };
+    let dh = 2.0 * libm::sqrt(c1 * c2) * libm::sin((dh / 2.0).to_radians());
+
+    let l_mean = (l1 + l2) / 2.0;
+    let c_mean = (c1 + c2) / 2.0;
+    let h_mean = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if libm::fabs(h1 - h2) <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+
+    let cos_deg = |d: f64| libm::cos(d.to_radians());
+    let t = 1.0 - 0.17 * cos_deg(h_mean - 30.0)
+        + 0.24 * cos_deg(2.0 * h_mean)
+        + 0.32 * cos_deg(3.0 * h_mean + 6.0)
+        - 0.20 * cos_deg(4.0 * h_mean - 63.0);
+    let d_theta = 30.0 * libm::exp(-libm::pow((h_mean - 275.0) / 25.0, 2.0));
+    let rc = 2.0 * chroma_weight(c_mean);
+    let l50 = (l_mean - 50.0) * (l_mean - 50.0);
+    let sl = 1.0 + 0.015 * l50 / libm::sqrt(20.0 + l50);
+    let sc = 1.0 + 0.045 * c_mean;
+    let sh = 1.0 + 0.015 * c_mean * t;
+    let rt = -libm::sin((2.0 * d_theta).to_radians()) * rc;
+
+    let (dl, dc, dh) = (dl / sl, dc / sc, dh / sh);
+    libm::sqrt(dl * dl + dc * dc + dh * dh + rt * dc * dh)
+}
+
+impl Luv {
+    /// CIE 1976 color difference ΔE\*uv (Euclidean distance).
+    pub fn delta_e(&self, other: &Luv) -> f32 {
+        let dl = self.l - other.l;
+        let du = self.u - other.u;
+        let dv = self.v - other.v;
+        libm::sqrtf(dl * dl + du * du + dv * dv)
+    }
+}
+
+/// Conversion of measurements into CIELAB and CIELUV
+///
+/// Measurements are converted into XYZ with a calibration matrix and
+/// related to the white point of an illuminant. The luminance of the
+/// reference white is taken from a measurement of a white target, since
+/// the XYZ values scale with the measurement counts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabConverter {
+    matrix: CalibrationMatrix,
+    illuminant: Illuminant,
+    white_luminance: f32,
+}
+
+impl LabConverter {
+    /// Create a converter with a white luminance of 1.0.
+    pub fn new(matrix: CalibrationMatrix, illuminant: Illuminant) -> Self {
+        LabConverter {
+            matrix,
+            illuminant,
+            white_luminance: 1.0,
+        }
+    }
+
+    /// Set the luminance (Y) of the reference white.
+    pub fn with_white_luminance(mut self, luminance: f32) -> Self {
+        self.white_luminance = luminance;
+        self
+    }
+
+    /// Capture the reference white luminance from a measurement of a
+    /// white target.
+    ///
+    /// The measurement must be taken with the same gain and integration
+    /// time as the measurements to convert.
+    pub fn capture_white_reference(&mut self, measurement: &AllChannelMeasurement) {
+        self.white_luminance = self.matrix.to_xyz(measurement).y;
+    }
+
+    /// Reference white in the scale of the converted measurements.
+    pub fn white(&self) -> Xyz {
+        let white = self.illuminant.white_point();
+        Xyz {
+            x: white.x * self.white_luminance,
+            y: self.white_luminance,
+            z: white.z * self.white_luminance,
+        }
+    }
+
+    /// Convert a measurement into CIELAB.
+    pub fn to_lab(&self, measurement: &AllChannelMeasurement) -> Lab {
+        self.matrix.to_xyz(measurement).to_lab(&self.white())
+    }
+
+    /// Convert a measurement into CIELUV.
+    pub fn to_luv(&self, measurement: &AllChannelMeasurement) -> Luv {
+        self.matrix.to_xyz(measurement).to_luv(&self.white())
+    }
+}
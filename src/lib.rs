@@ -10,17 +10,47 @@
 //! - Enable/disable the RGB converter interrupt generation.
 //! - Set the RGB converter interrupt clear channel low/high thresholds.
 //! - Set the RGB converter interrupt persistence.
+//! - Configure the RGB converter interrupt in one step.
+//! - Wait for an RGB converter interrupt on the INT pin (async).
 //! - Set the number of integration cycles.
 //! - Enable/disable the wait feature.
 //! - Set the number of wait time cycles.
 //! - Enable/disable the *wait long* setting.
+//! - Set and read the integration time and the wait time in microseconds.
+//! - Compute and apply a low-power duty cycle for a sample period and
+//!   estimate the average supply current.
+//! - Apply a complete configuration at once.
+//! - Read back the current configuration from the device.
 //! - Read status of RGB converter.
+//! - Clear the RGB converter interrupt.
+//! - Service an RGB converter interrupt (read status and channels, clear interrupt).
 //! - Read the clear (unfiltered) channel measurement.
 //! - Read the red channel measurement.
 //! - Read the green channel measurement.
 //! - Read the blue channel measurement.
 //! - Read the measurement of all channels at once.
+//! - Perform a single measurement waiting with a delay provider.
+//! - Detect digital and analog saturation of the measurements.
+//! - Enforce the power and enable sequence at compile time with a type-state API.
+//! - Access the registers through typed structs or as raw values.
+//! - Keep a shadow copy of the configuration registers to skip unchanged
+//!   writes, answer getters without bus traffic and detect drift.
+//! - Detect a device power-on reset and restore the configuration.
 //! - Read the device ID.
+//! - Select the device variant and verify it matches the device found.
+//! - Calculate the illuminance and correlated color temperature.
+//! - Estimate and remove the IR component of the measurements.
+//! - Automatically range the gain and integration time.
+//! - Convert measurements into CIE 1931 XYZ and xy chromaticity.
+//! - Calibrate the XYZ conversion matrix from reference samples.
+//! - Convert measurements into linear RGB and 8-bit sRGB with white balance.
+//! - Convert measurements into CIELAB and CIELUV and compute color
+//!   differences (ΔE76, ΔE94 and ΔE2000).
+//! - Classify colors with a trained nearest-neighbour classifier.
+//! - Drive several sensors behind a TCA9548A-style I²C multiplexer.
+//! - Measure with an array of sensors over the same time window.
+//! - Test application logic without hardware with a behavioural simulator
+//!   (`sim` feature).
 //!
 //! ## The device
 //!
@@ -45,7 +75,8 @@
 //! Datasheet:
 //! - [TCS3472](https://ams.com/documents/20143/36005/TCS3472_DS000390_2-00.pdf)
 //!
-//! This driver is compatible with the devices TCS34725 and TCS34727.
+//! This driver is compatible with the devices TCS34721, TCS34723, TCS34725
+//! and TCS34727.
 //!
 //! ## Usage examples (see also examples folder)
 //!
@@ -82,6 +113,19 @@
 //!          clear, red, green, blue);
 //! ```
 //!
+//! ### Use a TCS34721 or TCS34723 device at address 0x39
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use tcs3472::{DeviceVariant, Tcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new_with_variant(dev, DeviceVariant::Tcs34723);
+//! // Fails if the device ID does not match the selected variant.
+//! sensor.probe().unwrap();
+//! sensor.enable().unwrap();
+//! ```
+//!
 //! ### Read all the channels at once
 //!
 //! ```no_run
@@ -103,6 +147,42 @@
 //!          measurement.blue);
 //! ```
 //!
+//! ### Perform a single measurement
+//!
+//! ```no_run
+//! use linux_embedded_hal::{Delay, I2cdev};
+//! use tcs3472::Tcs3472;
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new(dev);
+//! sensor.set_integration_cycles(42).unwrap();
+//! // Power down again after the measurement.
+//! let m = sensor.measure(&mut Delay, true).unwrap();
+//! println!("Measurements: clear = {}, red = {}, green = {}, blue = {}",
+//!          m.clear, m.red, m.green, m.blue);
+//! ```
+//!
+//! ### Detect saturated measurements
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use tcs3472::{Saturation, Tcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new(dev);
+//! sensor.set_integration_cycles(10).unwrap();
+//! sensor.enable().unwrap();
+//! sensor.enable_rgbc().unwrap();
+//! while !sensor.is_rgbc_status_valid().unwrap() {
+//!     // wait for measurement to be available
+//! };
+//! let (measurement, saturation) = sensor.read_all_channels_with_saturation().unwrap();
+//! if saturation.clear != Saturation::None {
+//!     println!("Clear channel saturated ({:?}). Full scale: {}",
+//!              saturation.clear, sensor.full_scale_count());
+//! }
+//! ```
+//!
 //! ### Change the RGB converter gain and integration cycles
 //!
 //! ```no_run
@@ -149,33 +229,465 @@
 //! sensor.enable_rgbc_interrupts().unwrap();
 //! ```
 //!
+//! ### Calculate the illuminance and correlated color temperature
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use tcs3472::{LuxCoefficients, RgbCGain, Tcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new(dev);
+//! sensor.set_rgbc_gain(RgbCGain::_4x).unwrap();
+//! sensor.set_integration_cycles(42).unwrap();
+//! sensor.enable().unwrap();
+//! sensor.enable_rgbc().unwrap();
+//! while !sensor.is_rgbc_status_valid().unwrap() {
+//!     // wait for measurement to be available
+//! };
+//! let measurement = sensor.read_all_channels().unwrap();
+//! let result = LuxCoefficients::default().calculate(&measurement, RgbCGain::_4x, 42);
+//! println!("Illuminance: {} lux, CCT: {:?} K", result.lux(), result.color_temperature);
+//! ```
+//!
+//! ### Convert a measurement into CIE XYZ and chromaticity
+//!
+//! ```no_run
+//! use linux_embedded_hal::{Delay, I2cdev};
+//! use tcs3472::{CalibrationMatrix, Tcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new(dev);
+//! let measurement = sensor.measure(&mut Delay, true).unwrap();
+//! // Use a matrix derived with a reference meter or a rough approximation.
+//! let xyz = CalibrationMatrix::tcs3414_approximation().to_xyz(&measurement);
+//! if let Some(xy) = xyz.chromaticity() {
+//!     println!("x = {}, y = {}", xy.x, xy.y);
+//! }
+//! ```
+//!
+//! ### Calibrate the XYZ conversion matrix
+//!
+//! ```no_run
+//! use linux_embedded_hal::{Delay, I2cdev};
+//! use tcs3472::{CalibrationChannels, CalibrationMatrix, CalibrationSample, Tcs3472, Xyz};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new(dev);
+//! // XYZ values of the reference targets from a spectrophotometer
+//! let references = [
+//!     Xyz { x: 41.2, y: 21.3, z: 1.9 },
+//!     Xyz { x: 35.8, y: 71.5, z: 11.9 },
+//!     Xyz { x: 18.0, y: 7.2, z: 95.0 },
+//!     Xyz { x: 95.0, y: 100.0, z: 108.9 },
+//! ];
+//! let mut samples = [CalibrationSample {
+//!     measurement: Default::default(),
+//!     reference: references[0],
+//! }; 4];
+//! for (sample, reference) in samples.iter_mut().zip(references) {
+//!     // present the reference target to the sensor
+//!     sample.measurement = sensor.measure(&mut Delay, false).unwrap();
+//!     sample.reference = reference;
+//! }
+//! let matrix = CalibrationMatrix::solve(&samples, CalibrationChannels::Rgbc).unwrap();
+//! let mut residuals = [0.0; 4];
+//! matrix.residuals(&samples, &mut residuals);
+//! println!("Residuals: {:?}", residuals);
+//! ```
+//!
+//! ### Remove the IR component before further processing
+//!
+//! ```no_run
+//! use linux_embedded_hal::{Delay, I2cdev};
+//! use tcs3472::{CalibrationMatrix, Tcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new(dev);
+//! let measurement = sensor.measure(&mut Delay, false).unwrap();
+//! let compensated = measurement.ir_compensated();
+//! println!("IR: {}", compensated.ir);
+//! let xyz = CalibrationMatrix::tcs3414_approximation().to_xyz(&compensated.measurement);
+//! println!("Y = {}", xyz.y);
+//! ```
+//!
+//! ### Enforce the power and enable sequence with the type-state API
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use tcs3472::{Tcs3472, TypedTcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let sensor = TypedTcs3472::new(Tcs3472::new(dev)).unwrap();
+//! // sensor.read_all_channels() does not compile while the device is off
+//! let sensor = sensor.into_idle().ok().unwrap();
+//! let mut sensor = sensor.into_active().ok().unwrap();
+//! while !sensor.is_rgbc_status_valid().unwrap() {
+//!     // wait for measurement to be available
+//! }
+//! let measurement = sensor.read_all_channels().unwrap();
+//! println!("Clear: {}", measurement.clear);
+//! let sensor = sensor.into_off().ok().unwrap();
+//! ```
+//!
+//! Reading the channels while the device is off does not compile:
+//!
+//! ```compile_fail
+//! use linux_embedded_hal::I2cdev;
+//! use tcs3472::{Tcs3472, TypedTcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = TypedTcs3472::new(Tcs3472::new(dev)).unwrap();
+//! let measurement = sensor.read_all_channels();
+//! ```
+//!
+//! ### Access the registers directly
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use tcs3472::{registers::Control, RgbCGain, Tcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new(dev);
+//! sensor.write_typed_register(Control { gain: RgbCGain::_4x }).unwrap();
+//! let control: Control = sensor.read_typed_register().unwrap();
+//! let id = sensor.read_register(0x12).unwrap();
+//! println!("Gain: {:?}, ID: {}", control.gain, id);
+//! ```
+//!
+//! ### Detect configuration drift
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use tcs3472::{Config, RgbCGain, Tcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new(dev);
+//! let config = Config::builder().gain(RgbCGain::_16x).build().unwrap();
+//! sensor.apply_config(&config).unwrap();
+//! assert_eq!(Some(config), sensor.cached_config());
+//! // later on
+//! if !sensor.verify().unwrap() {
+//!     println!("The device lost its configuration");
+//! }
+//! // or detect a power-on reset and restore the configuration
+//! if let Some(reset) = sensor.recover_from_reset().unwrap() {
+//!     println!("Device reset detected: {:?}", reset);
+//! }
+//! ```
+//!
+//! ### Convert a measurement into sRGB with white balance
+//!
+//! ```no_run
+//! use linux_embedded_hal::{Delay, I2cdev};
+//! use tcs3472::{GamutClipping, Tcs3472, WhiteBalance};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new(dev);
+//! let mut balance = WhiteBalance::new().with_clipping(GamutClipping::Normalize);
+//! // present a white target to the sensor
+//! let white = sensor.measure(&mut Delay, false).unwrap();
+//! balance.capture_white_reference(&white);
+//!
+//! let measurement = sensor.measure(&mut Delay, false).unwrap();
+//! let color = balance.to_srgb8(&measurement);
+//! println!("R = {}, G = {}, B = {}", color.red, color.green, color.blue);
+//! ```
+//!
+//! ### Compare a color against a reference
+//!
+//! ```no_run
+//! use linux_embedded_hal::{Delay, I2cdev};
+//! use tcs3472::{CalibrationMatrix, Illuminant, LabConverter, Tcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new(dev);
+//! let mut converter = LabConverter::new(CalibrationMatrix::tcs3414_approximation(), Illuminant::D65);
+//! // present a white target to the sensor
+//! let white = sensor.measure(&mut Delay, false).unwrap();
+//! converter.capture_white_reference(&white);
+//!
+//! let golden = converter.to_lab(&sensor.measure(&mut Delay, false).unwrap());
+//! let sample = converter.to_lab(&sensor.measure(&mut Delay, false).unwrap());
+//! if sample.delta_e2000(&golden) > 2.0 {
+//!     println!("Color out of tolerance");
+//! }
+//! ```
+//!
+//! ### Classify colors
+//!
+//! ```no_run
+//! use linux_embedded_hal::{Delay, I2cdev};
+//! use tcs3472::{Classification, ColorClassifier, Tcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new(dev);
+//! let mut classifier = ColorClassifier::<&str, 8>::new().with_rejection_threshold(0.03);
+//! for label in ["red", "orange", "brown"] {
+//!     // present a sample of the class to the sensor
+//!     let measurement = sensor.measure(&mut Delay, false).unwrap();
+//!     classifier.add_sample(label, &measurement).unwrap();
+//! }
+//!
+//! let measurement = sensor.measure(&mut Delay, false).unwrap();
+//! match classifier.classify(&measurement) {
+//!     Classification::Known { label, confidence, .. } => {
+//!         println!("{} (confidence {})", label, confidence)
+//!     }
+//!     Classification::Unknown => println!("Unknown color"),
+//! }
+//! ```
+//!
+//! ### Automatically range the gain and integration time
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use tcs3472::{AutoRange, Tcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new(dev);
+//! let mut auto_range = AutoRange::new().with_integration_cycles_range(1, 64);
+//! sensor.enable().unwrap();
+//! sensor.enable_rgbc().unwrap();
+//! loop {
+//!     // wait for the integration time to elapse
+//!     if let Some(result) = sensor.read_all_channels_auto_ranged(&mut auto_range).unwrap() {
+//!         println!("Gain: {:?}, integration cycles: {}, normalized clear: {}",
+//!                  result.gain, result.integration_cycles, result.normalized().clear);
+//!     }
+//! }
+//! ```
+//!
+//! ### Apply a complete configuration at once
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use tcs3472::{Config, EnableFlags, RgbCGain, RgbCInterruptPersistence, Tcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new(dev);
+//! let config = Config::builder()
+//!     .gain(RgbCGain::_16x)
+//!     .integration_cycles(42)
+//!     .rgbc_interrupt_low_threshold(1024)
+//!     .rgbc_interrupt_high_threshold(61440)
+//!     .rgbc_interrupt_persistence(RgbCInterruptPersistence::_5)
+//!     .enable_flags(EnableFlags { power_on: true, ..Default::default() })
+//!     .build()
+//!     .unwrap();
+//! sensor.apply_config(&config).unwrap();
+//! assert_eq!(config, sensor.read_config().unwrap());
+//! ```
+//!
+//! ### Read back the configuration after an MCU reset
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use tcs3472::Tcs3472;
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new(dev);
+//! let enable = sensor.read_enable_flags().unwrap();
+//! let gain = sensor.read_rgbc_gain().unwrap();
+//! let cycles = sensor.read_integration_cycles().unwrap();
+//! println!("Powered on: {}, gain: {:?}, integration cycles: {}",
+//!          enable.power_on, gain, cycles);
+//! ```
+//!
+//! ### Measure periodically with a low-power duty cycle
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use tcs3472::{DutyCycle, Tcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Tcs3472::new(dev);
+//! // a measurement every second with an integration time of 24ms
+//! let duty_cycle = DutyCycle::new(1_000_000, 24_000).unwrap();
+//! println!("Period: {}us, average current: {}uA",
+//!          duty_cycle.period_us(), duty_cycle.average_current_ua());
+//! sensor.apply_duty_cycle(&duty_cycle).unwrap();
+//! sensor.enable().unwrap();
+//! sensor.enable_rgbc().unwrap();
+//! ```
+//!
+//! ### Use several sensors behind an I²C multiplexer
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use tcs3472::{Config, EnableFlags, I2cMux, RgbCGain, SensorArray, Tcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mux = I2cMux::new(dev);
+//! let mut sensors = SensorArray::new([
+//!     Tcs3472::new(mux.channel(0).unwrap()),
+//!     Tcs3472::new(mux.channel(1).unwrap()),
+//!     Tcs3472::new(mux.channel(2).unwrap()),
+//! ]);
+//! let config = Config::builder()
+//!     .gain(RgbCGain::_16x)
+//!     .enable_flags(EnableFlags { power_on: true, ..Default::default() })
+//!     .build()
+//!     .unwrap();
+//! sensors.apply_config(&config).unwrap();
+//! // enable the RGB converters and wait for the integration time
+//! let measurements = sensors.read_all_channels().unwrap();
+//! for (index, m) in measurements.iter().enumerate() {
+//!     println!("Sensor {}: clear = {}", index, m.clear);
+//! }
+//! ```
+//!
+//! ### Measure with an array of sensors over the same time window
+//!
+//! ```no_run
+//! use linux_embedded_hal::{Delay, I2cdev};
+//! use std::time::Instant;
+//! use tcs3472::{I2cMux, SensorArray, Tcs3472};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mux = I2cMux::new(dev);
+//! let mut sensors = SensorArray::new([0, 1, 2, 3].map(|channel| {
+//!     Tcs3472::new(mux.channel(channel).unwrap())
+//! }));
+//! let start = Instant::now();
+//! let timestamp = || start.elapsed().as_micros() as u64;
+//! let results = sensors.trigger(&mut Delay, timestamp, false).unwrap();
+//! for (index, result) in results.iter().enumerate() {
+//!     println!("Sensor {}: clear = {}, skew = {}us",
+//!              index, result.measurement.clear, result.skew);
+//! }
+//! ```
+//!
 //! ### Using async driver
 //!
+//! If the `async` feature is enabled, the `Tcs3472Async` driver provides
+//! the same interface as `Tcs3472` based on the `embedded-hal-async` traits.
+//! The blocking `Tcs3472` driver remains available, so that both can be
+//! used in the same build.
+//!
+//! The async driver can also wait for RGB converter interrupts on the INT
+//! pin through the `embedded-hal-async` `Wait` trait. See
+//! `Tcs3472Async::wait_for_rgbc_interrupt()`.
+//!
 //! See `examples/embassy.rs` to see the code for the async case.
+//!
+//! ### Simulating the device
+//!
+//! If the `sim` feature is enabled, the `sim` module provides a simulated
+//! device with the register map, the power and integration timing and the
+//! interrupt logic of the TCS3472. Its I²C and delay handles can be given
+//! to the drivers in order to test application logic on the host.
+//! See the `sim` module for an example.
 
 #![deny(unsafe_code, missing_docs)]
 #![no_std]
 
+mod array;
+#[cfg(feature = "async")]
+pub use crate::array::SensorArrayAsync;
+pub use crate::array::{SensorArray, SensorArrayError, TimedMeasurement};
+mod auto_range;
+pub use crate::auto_range::{AutoRange, AutoRangedMeasurement, NormalizedMeasurement};
+mod classifier;
+pub use crate::classifier::{Classification, ClassifierError, ColorClassifier};
+mod config;
+pub use crate::config::{Config, ConfigBuilder, ConfigError};
+mod calibration;
+pub use crate::calibration::{CalibrationChannels, CalibrationError, CalibrationSample};
 mod configuration;
 mod interface;
 use crate::interface::{BitFlags, Register, DEVICE_ADDRESS};
+mod ir;
+pub use crate::ir::IrCompensated;
+mod lab;
+pub use crate::lab::{Illuminant, Lab, LabConverter, Luv};
+mod lux;
+pub use crate::lux::{Illuminance, LuxCoefficients};
+mod mux;
+pub use crate::mux::{I2cMux, MuxChannel};
 mod reading;
+pub mod registers;
+mod reset;
+mod rgb;
+mod schedule;
+pub use crate::schedule::{DutyCycle, DutyCycleError};
+mod shadow;
+#[cfg(feature = "sim")]
+pub mod sim;
+pub use crate::rgb::{GamutClipping, LinearRgb, Srgb8, WhiteBalance};
+use crate::shadow::Shadow;
 mod types;
-pub use crate::types::{AllChannelMeasurement, Error, RgbCGain, RgbCInterruptPersistence};
+mod typestate;
+#[cfg(feature = "async")]
+pub use crate::typestate::TypedTcs3472Async;
+pub use crate::typestate::{state, ModeChangeError, TypedTcs3472};
+mod xyz;
+use crate::types::full_scale_count;
+pub use crate::types::{
+    AllChannelMeasurement, ChannelSaturation, DeviceReset, DeviceVariant, EnableFlags, Error,
+    RgbCGain, RgbCInterruptPersistence, RgbCInterruptTrigger, Saturation, Status,
+};
+pub use crate::xyz::{CalibrationMatrix, Chromaticity, Xyz};
 
+#[maybe_async_cfg::maybe(
+    sync(self = "Tcs3472"),
+    async(feature = "async", self = "Tcs3472Async")
+)]
 /// TCS3472 device driver.
+///
+/// `Tcs3472` uses the blocking `embedded-hal` traits. If the `async`
+/// feature is enabled, `Tcs3472Async` provides the same interface
+/// based on the `embedded-hal-async` traits.
 #[derive(Debug)]
 pub struct Tcs3472<I2C> {
     /// The concrete I²C device implementation.
     i2c: I2C,
-    /// Enable register status
-    enable: u8,
+    /// Device I²C address
+    address: u8,
+    /// Expected device variant
+    variant: Option<DeviceVariant>,
+    /// Shadow copy of the writable registers
+    shadow: Shadow,
 }
 
+#[maybe_async_cfg::maybe(
+    sync(self = "Tcs3472"),
+    async(feature = "async", self = "Tcs3472Async")
+)]
 impl<I2C> Tcs3472<I2C> {
     /// Create new instance of the TCS3472 device.
+    ///
+    /// This uses the address 0x29 of the TCS34725 and TCS34727 devices.
+    /// See [`new_with_variant()`](#method.new_with_variant) for other devices.
     pub fn new(i2c: I2C) -> Self {
-        Tcs3472 { i2c, enable: 0 }
+        Tcs3472 {
+            i2c,
+            address: DEVICE_ADDRESS,
+            variant: None,
+            shadow: Shadow::default(),
+        }
+    }
+
+    /// Create new instance of the TCS3472 device for a device variant.
+    ///
+    /// This selects the I²C address of the variant. Use
+    /// [`probe()`](#method.probe) to verify that the device found matches it.
+    pub fn new_with_variant(i2c: I2C, variant: DeviceVariant) -> Self {
+        Tcs3472 {
+            i2c,
+            address: variant.address(),
+            variant: Some(variant),
+            shadow: Shadow::default(),
+        }
+    }
+
+    /// Get the full-scale count of the RGBC channels for the configured
+    /// number of integration cycles.
+    ///
+    /// This corresponds to `1024 * number_of_cycles`, up to 65535.
+    /// The driver assumes the device power-on default of 1 integration
+    /// cycle until the number of integration cycles is set or read back.
+    pub fn full_scale_count(&self) -> u16 {
+        full_scale_count(self.shadow.integration_cycles())
     }
 
     /// Destroy driver instance, return I²C bus instance.
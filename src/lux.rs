@@ -0,0 +1,109 @@
+//! Illuminance and correlated color temperature calculation.
+//!
+//! This follows the algorithm described in the ams design note DN40
+//! "Lux and CCT Calculations using ams Color Sensors". All calculations use
+//! integer arithmetic so that no floating-point support is needed.
+
+use crate::{AllChannelMeasurement, RgbCGain};
+
+/// Coefficients for the illuminance and color temperature calculation
+///
+/// The defaults correspond to a bare TCS34725 without any glass or
+/// diffuser in front of it. If the sensor is placed behind glass, the
+/// glass attenuation and device factor should be determined for the
+/// concrete design as described in DN40.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LuxCoefficients {
+    /// Red channel coefficient in thousandths.
+    pub red: i32,
+    /// Green channel coefficient in thousandths.
+    pub green: i32,
+    /// Blue channel coefficient in thousandths.
+    pub blue: i32,
+    /// Glass attenuation factor in thousandths.
+    pub glass_attenuation: u32,
+    /// Device factor.
+    pub device_factor: u32,
+    /// Color temperature coefficient.
+    pub color_temperature: i32,
+    /// Color temperature offset in kelvin.
+    pub color_temperature_offset: i32,
+}
+
+impl Default for LuxCoefficients {
+    fn default() -> Self {
+        LuxCoefficients {
+            red: 136,
+            green: 1000,
+            blue: -444,
+            glass_attenuation: 1000,
+            device_factor: 310,
+            color_temperature: 3810,
+            color_temperature_offset: 1391,
+        }
+    }
+}
+
+/// Result of the illuminance and color temperature calculation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Illuminance {
+    /// Illuminance in millilux.
+    pub millilux: u32,
+    /// Correlated color temperature in kelvin.
+    ///
+    /// This is `None` if it cannot be determined, for example because
+    /// there is no red component in the measurement.
+    pub color_temperature: Option<u32>,
+}
+
+impl Illuminance {
+    /// Illuminance in lux.
+    pub fn lux(&self) -> f32 {
+        self.millilux as f32 / 1000.0
+    }
+}
+
+impl LuxCoefficients {
+    /// Calculate the illuminance and correlated color temperature.
+    ///
+    /// The gain and number of integration cycles must be the ones with
+    /// which the measurement was taken. The number of integration cycles
+    /// is clamped to the valid range (1-256). The illuminance saturates at
+    /// `u32::MAX` millilux for extreme coefficients.
+    pub fn calculate(
+        &self,
+        measurement: &AllChannelMeasurement,
+        gain: RgbCGain,
+        integration_cycles: u16,
+    ) -> Illuminance {
+        let compensated = measurement.ir_compensated().measurement;
+        let red = i64::from(compensated.red);
+        let green = i64::from(compensated.green);
+        let blue = i64::from(compensated.blue);
+
+        let g2 =
+            i64::from(self.red) * red + i64::from(self.green) * green + i64::from(self.blue) * blue;
+        // The integration time is `cycles * 2.4ms = cycles * 12 / 5 ms`
+        // and the coefficients and glass attenuation are in thousandths.
+        let cycles = i64::from(integration_cycles.clamp(1, 256));
+        let counts_per_lux = cycles * 12 * i64::from(gain.multiplier()) * 1000;
+        let millilux = g2
+            .max(0)
+            .saturating_mul(i64::from(self.glass_attenuation))
+            .saturating_mul(i64::from(self.device_factor))
+            .saturating_mul(5)
+            / counts_per_lux;
+
+        let color_temperature = if red == 0 {
+            None
+        } else {
+            let ct = i64::from(self.color_temperature) * blue / red
+                + i64::from(self.color_temperature_offset);
+            u32::try_from(ct).ok()
+        };
+        Illuminance {
+            millilux: u32::try_from(millilux).unwrap_or(u32::MAX),
+            color_temperature,
+        }
+    }
+}
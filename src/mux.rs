@@ -0,0 +1,127 @@
+//! Channel-selecting I²C multiplexer for several sensors.
+
+use core::cell::{Cell, RefCell};
+use embedded_hal::i2c::{ErrorType, I2c, Operation};
+
+const MUX_ADDRESS: u8 = 0x70;
+const MUX_CHANNELS: u8 = 8;
+
+/// Channel-selecting I²C multiplexer like the TCA9548A
+///
+/// All TCS3472 devices of a variant share the same address, so several
+/// sensors on one bus need to sit behind a multiplexer. `I2cMux` takes
+/// the bus and hands out [`MuxChannel`] handles that implement `I2c`
+/// and can be given to separate drivers.
+///
+/// Each channel handle selects its downstream channel before a
+/// transaction. The selected channel is cached so that consecutive
+/// transactions on the same channel do not write to the multiplexer
+/// again.
+///
+/// The channel handles only implement the blocking `embedded-hal` `I2c`
+/// trait, since the bus is shared through a `RefCell` that cannot be held
+/// across an `.await`. For `SensorArrayAsync`, use a shared-bus wrapper of
+/// your async framework (e.g. a mutex-based I²C device) that selects the
+/// multiplexer channel before each transaction.
+#[derive(Debug)]
+pub struct I2cMux<I2C> {
+    i2c: RefCell<I2C>,
+    address: u8,
+    selected: Cell<Option<u8>>,
+}
+
+impl<I2C> I2cMux<I2C> {
+    /// Create a new multiplexer at the default address 0x70.
+    pub fn new(i2c: I2C) -> Self {
+        Self::new_with_address(i2c, MUX_ADDRESS)
+    }
+
+    /// Create a new multiplexer at an address.
+    ///
+    /// The TCA9548A address can be set in the range 0x70-0x77.
+    pub fn new_with_address(i2c: I2C, address: u8) -> Self {
+        I2cMux {
+            i2c: RefCell::new(i2c),
+            address,
+            selected: Cell::new(None),
+        }
+    }
+
+    /// Get a handle to a downstream channel (0-7).
+    ///
+    /// Returns `None` if the channel does not exist.
+    pub fn channel(&self, channel: u8) -> Option<MuxChannel<'_, I2C>> {
+        if channel < MUX_CHANNELS {
+            Some(MuxChannel { mux: self, channel })
+        } else {
+            None
+        }
+    }
+
+    /// Destroy multiplexer instance, return I²C bus instance.
+    pub fn destroy(self) -> I2C {
+        self.i2c.into_inner()
+    }
+}
+
+/// Downstream channel of an [`I2cMux`]
+#[derive(Debug)]
+pub struct MuxChannel<'a, I2C> {
+    mux: &'a I2cMux<I2C>,
+    channel: u8,
+}
+
+impl<I2C> Clone for MuxChannel<'_, I2C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I2C> Copy for MuxChannel<'_, I2C> {}
+
+impl<I2C: ErrorType> ErrorType for MuxChannel<'_, I2C> {
+    type Error = I2C::Error;
+}
+
+impl<I2C: I2c> MuxChannel<'_, I2C> {
+    fn with_channel<R>(
+        &mut self,
+        f: impl FnOnce(&mut I2C) -> Result<R, I2C::Error>,
+    ) -> Result<R, I2C::Error> {
+        let mux = self.mux;
+        let mut i2c = mux.i2c.borrow_mut();
+        if mux.selected.get() != Some(self.channel) {
+            mux.selected.set(None);
+            i2c.write(mux.address, &[1 << self.channel])?;
+            mux.selected.set(Some(self.channel));
+        }
+        f(&mut i2c)
+    }
+}
+
+impl<I2C: I2c> I2c for MuxChannel<'_, I2C> {
+    fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+        self.with_channel(|i2c| i2c.read(address, read))
+    }
+
+    fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        self.with_channel(|i2c| i2c.write(address, write))
+    }
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.with_channel(|i2c| i2c.write_read(address, write, read))
+    }
+
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.with_channel(|i2c| i2c.transaction(address, operations))
+    }
+}
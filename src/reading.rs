@@ -1,16 +1,23 @@
-use crate::{AllChannelMeasurement, BitFlags, Error, Register, Tcs3472, DEVICE_ADDRESS};
-#[cfg(not(feature = "async"))]
-use embedded_hal::i2c::I2c;
+use crate::interface::CYCLE_TIME_US;
+use crate::registers::TypedRegister;
 #[cfg(feature = "async")]
-use embedded_hal_async::i2c::I2c as AsyncI2c;
+use crate::Tcs3472Async;
+use crate::{
+    AllChannelMeasurement, BitFlags, ChannelSaturation, DeviceVariant, Error, Register, Status,
+    Tcs3472,
+};
+#[cfg(feature = "async")]
+use embedded_hal::digital::Error as _;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+#[cfg(feature = "async")]
+use embedded_hal_async::{delay::DelayNs as AsyncDelayNs, digital::Wait, i2c::I2c as AsyncI2c};
 
 #[maybe_async_cfg::maybe(
     sync(
-        cfg(not(feature = "async")),
         self = "Tcs3472",
-        idents(AsyncI2c(sync = "I2c"))
+        idents(AsyncI2c(sync = "I2c"), AsyncDelayNs(sync = "DelayNs"))
     ),
-    async(feature = "async", keep_self)
+    async(feature = "async", self = "Tcs3472Async")
 )]
 impl<I2C, E> Tcs3472<I2C>
 where
@@ -21,8 +28,41 @@ where
     /// Indicates that the RGBC channels have completed an integration cycle.
     #[allow(clippy::wrong_self_convention)]
     pub async fn is_rgbc_status_valid(&mut self) -> Result<bool, Error<E>> {
-        let status = self.read_register(Register::STATUS).await?;
-        Ok((status & BitFlags::RGBC_VALID) != 0)
+        let status = self.read_typed_register::<Status>().await?;
+        Ok(status.rgbc_valid)
+    }
+
+    /// Read the RGB converter status.
+    ///
+    /// This includes whether the RGBC channels have completed an integration
+    /// cycle and whether an RGBC interrupt is asserted.
+    pub async fn read_status(&mut self) -> Result<Status, Error<E>> {
+        self.read_typed_register().await
+    }
+
+    /// Clear the RGB converter interrupt.
+    ///
+    /// The interrupt stays asserted (and the INT pin low) until cleared.
+    pub async fn clear_rgbc_interrupt(&mut self) -> Result<(), Error<E>> {
+        let command = BitFlags::CMD | BitFlags::CMD_SPECIAL_FN | BitFlags::RGBC_INT_CLEAR;
+        self.i2c
+            .write(self.address, &[command])
+            .await
+            .map_err(Error::I2C)
+    }
+
+    /// Service an RGB converter interrupt.
+    ///
+    /// Reads the status and the measurement data of all channels at once
+    /// and then clears the RGBC interrupt.
+    pub async fn service_rgbc_interrupt(
+        &mut self,
+    ) -> Result<(Status, AllChannelMeasurement), Error<E>> {
+        let mut data = [0; 9];
+        self.read_registers(Register::STATUS, &mut data).await?;
+        self.clear_rgbc_interrupt().await?;
+        let status = Status::from_bits(data[0]);
+        Ok((status, Self::decode_channels(&data[1..])))
     }
 
     /// Read the clear (unfiltered) channel measurement data.
@@ -55,42 +95,212 @@ where
     pub async fn read_all_channels(&mut self) -> Result<AllChannelMeasurement, Error<E>> {
         let mut data = [0; 8];
         self.read_registers(Register::CDATA, &mut data).await?;
-        Ok(AllChannelMeasurement {
+        Ok(Self::decode_channels(&data))
+    }
+
+    /// Read the measurement data of all channels at once and classify
+    /// their saturation.
+    ///
+    /// The saturation is determined with the full-scale count for the
+    /// configured number of integration cycles.
+    /// See [`full_scale_count()`](#method.full_scale_count).
+    pub async fn read_all_channels_with_saturation(
+        &mut self,
+    ) -> Result<(AllChannelMeasurement, ChannelSaturation), Error<E>> {
+        let measurement = self.read_all_channels().await?;
+        let saturation = measurement.saturation(self.full_scale_count());
+        Ok((measurement, saturation))
+    }
+
+    /// Perform a single measurement of all channels.
+    ///
+    /// This powers the device on, waits for the warm-up time, enables the
+    /// RGB converter, waits for the configured integration time and then
+    /// polls the status until the measurement is valid before reading it.
+    /// If the integration time is not known, it is read from the device
+    /// first.
+    /// If the measurement does not become valid within 10% of the
+    /// integration time plus 4 cycles, `Error::Timeout` is returned.
+    ///
+    /// If `power_down` is `true`, the RGB converter is disabled and the
+    /// device is put to sleep after the measurement.
+    pub async fn measure<D: AsyncDelayNs>(
+        &mut self,
+        delay: &mut D,
+        power_down: bool,
+    ) -> Result<AllChannelMeasurement, Error<E>> {
+        let cycles = self.timing_integration_cycles().await?;
+        self.power_on_rgbc_disabled().await?;
+        delay.delay_us(CYCLE_TIME_US).await;
+        self.enable_rgbc().await?;
+        delay.delay_us(u32::from(cycles) * CYCLE_TIME_US).await;
+        self.wait_for_rgbc_status_valid(delay, cycles).await?;
+        let measurement = self.read_all_channels().await?;
+        if power_down {
+            self.power_off().await?;
+        }
+        Ok(measurement)
+    }
+
+    /// Number of integration cycles for timing a measurement.
+    ///
+    /// The number is read back from the device if it is not known, e.g.
+    /// after the microcontroller was reset.
+    pub(crate) async fn timing_integration_cycles(&mut self) -> Result<u16, Error<E>> {
+        if self.shadow.get(Register::ATIME).is_some() {
+            Ok(self.shadow.integration_cycles())
+        } else {
+            self.read_integration_cycles().await
+        }
+    }
+
+    /// Power the device on with the RGB converter disabled.
+    pub(crate) async fn power_on_rgbc_disabled(&mut self) -> Result<(), Error<E>> {
+        let enable = (self.shadow.enable() | BitFlags::POWER_ON) & !BitFlags::RGBC_EN;
+        self.write_enable(enable).await
+    }
+
+    /// Poll the status until the measurement is valid.
+    ///
+    /// Returns `Error::Timeout` if the measurement does not become valid
+    /// within 10% of the integration time plus 4 cycles.
+    pub(crate) async fn wait_for_rgbc_status_valid<D: AsyncDelayNs>(
+        &mut self,
+        delay: &mut D,
+        integration_cycles: u16,
+    ) -> Result<(), Error<E>> {
+        let mut remaining_polls = integration_cycles / 10 + 4;
+        while !self.is_rgbc_status_valid().await? {
+            if remaining_polls == 0 {
+                return Err(Error::Timeout);
+            }
+            remaining_polls -= 1;
+            delay.delay_us(CYCLE_TIME_US).await;
+        }
+        Ok(())
+    }
+
+    /// Disable the RGB converter and power the device off.
+    pub(crate) async fn power_off(&mut self) -> Result<(), Error<E>> {
+        let enable = self.shadow.enable() & !(BitFlags::POWER_ON | BitFlags::RGBC_EN);
+        self.write_enable(enable).await
+    }
+
+    fn decode_channels(data: &[u8]) -> AllChannelMeasurement {
+        AllChannelMeasurement {
             clear: u16::from(data[1]) << 8 | u16::from(data[0]),
             red: u16::from(data[3]) << 8 | u16::from(data[2]),
             green: u16::from(data[5]) << 8 | u16::from(data[4]),
             blue: u16::from(data[7]) << 8 | u16::from(data[6]),
-        })
+        }
     }
 
     /// Read the device ID.
     ///
     /// The value returned corresponds to the part number identification:
-    /// - `0x44` => `TCS34725`
-    /// - `0x4D` => `TCS34727`
+    /// - `0x44` => `TCS34721` or `TCS34725`
+    /// - `0x4D` => `TCS34723` or `TCS34727`
     pub async fn read_device_id(&mut self) -> Result<u8, Error<E>> {
         self.read_register(Register::ID).await
     }
 
-    async fn read_register(&mut self, register: u8) -> Result<u8, Error<E>> {
-        let command = BitFlags::CMD | register;
+    /// Read the device variant.
+    ///
+    /// The variant is decoded from the device ID and the I²C address in use.
+    /// Returns `Error::UnexpectedDeviceId` if the ID is unknown.
+    pub async fn read_device_variant(&mut self) -> Result<DeviceVariant, Error<E>> {
+        let id = self.read_device_id().await?;
+        DeviceVariant::from_id_and_address(id, self.address).ok_or(Error::UnexpectedDeviceId)
+    }
+
+    /// Verify that the device is present and matches the selected variant.
+    ///
+    /// If the driver was created with [`new()`](#method.new), any known
+    /// variant at the address in use is accepted.
+    /// Returns `Error::UnexpectedDeviceId` otherwise.
+    pub async fn probe(&mut self) -> Result<DeviceVariant, Error<E>> {
+        let variant = self.read_device_variant().await?;
+        match self.variant {
+            Some(expected) if expected != variant => Err(Error::UnexpectedDeviceId),
+            _ => Ok(variant),
+        }
+    }
+
+    /// Read a typed register.
+    ///
+    /// See the [`registers`](crate::registers) module. Returns
+    /// `Error::InvalidRegisterValue` if the value cannot be decoded.
+    pub async fn read_typed_register<R: TypedRegister>(&mut self) -> Result<R, Error<E>> {
+        let value = self.read_register(R::ADDRESS).await?;
+        R::decode(value).ok_or(Error::InvalidRegisterValue)
+    }
+
+    /// Read a raw register value.
+    ///
+    /// This gives access to anything the rest of the API does not cover.
+    /// Returns `Error::InvalidInputData` if the register address is
+    /// beyond 0x1F.
+    pub async fn read_register(&mut self, register: u8) -> Result<u8, Error<E>> {
+        if register > BitFlags::CMD_ADDR {
+            return Err(Error::InvalidInputData);
+        }
         let mut data = [0];
-        self.i2c
-            .write_read(DEVICE_ADDRESS, &[command], &mut data)
-            .await
-            .map_err(Error::I2C)?;
+        self.read_registers(register, &mut data).await?;
         Ok(data[0])
     }
 
-    async fn read_registers(
+    /// Read contiguous registers and store the configuration registers
+    /// among them in the shadow copy.
+    pub(crate) async fn read_registers(
         &mut self,
         first_register: u8,
         data: &mut [u8],
     ) -> Result<(), Error<E>> {
-        let command = BitFlags::CMD | BitFlags::CMD_AUTO_INC | first_register;
+        self.read_block(first_register, data).await?;
+        self.shadow.store(first_register, data);
+        Ok(())
+    }
+
+    pub(crate) async fn read_block(
+        &mut self,
+        first_register: u8,
+        data: &mut [u8],
+    ) -> Result<(), Error<E>> {
+        let command = if data.len() > 1 {
+            BitFlags::CMD | BitFlags::CMD_AUTO_INC | first_register
+        } else {
+            BitFlags::CMD | first_register
+        };
         self.i2c
-            .write_read(DEVICE_ADDRESS, &[command], data)
+            .write_read(self.address, &[command], data)
             .await
             .map_err(Error::I2C)
     }
 }
+
+#[cfg(feature = "async")]
+impl<I2C, E> Tcs3472Async<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Wait for an RGB converter interrupt on the INT pin and service it.
+    ///
+    /// The INT pin is active low and stays asserted until the interrupt is
+    /// cleared, so an interrupt asserted before calling this method is not
+    /// missed. Once asserted, the status and the measurement data of all
+    /// channels are read and the interrupt is cleared.
+    ///
+    /// The interrupt generation must have been configured beforehand with
+    /// [`configure_rgbc_interrupt()`](#method.configure_rgbc_interrupt).
+    pub async fn wait_for_rgbc_interrupt<P: Wait>(
+        &mut self,
+        int_pin: &mut P,
+    ) -> Result<AllChannelMeasurement, Error<E>> {
+        int_pin
+            .wait_for_low()
+            .await
+            .map_err(|e| Error::Pin(e.kind()))?;
+        let (_, measurement) = self.service_rgbc_interrupt().await?;
+        Ok(measurement)
+    }
+}
@@ -0,0 +1,256 @@
+//! Typed register map.
+//!
+//! Each register is represented by a type that encodes into and decodes
+//! from the raw register value. The types can be read and written with
+//! [`Tcs3472::read_typed_register()`](crate::Tcs3472::read_typed_register)
+//! and [`Tcs3472::write_typed_register()`](crate::Tcs3472::write_typed_register).
+//! Registers not covered here can be accessed with
+//! [`Tcs3472::read_register()`](crate::Tcs3472::read_register) and
+//! [`Tcs3472::write_register()`](crate::Tcs3472::write_register).
+
+/// Enable register (ENABLE)
+pub use crate::EnableFlags as Enable;
+/// Status register (STATUS)
+pub use crate::Status;
+use crate::{BitFlags, Register, RgbCGain, RgbCInterruptPersistence};
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::Enable {}
+    impl Sealed for super::Status {}
+    impl Sealed for super::Atime {}
+    impl Sealed for super::Wtime {}
+    impl Sealed for super::Pers {}
+    impl Sealed for super::ConfigRegister {}
+    impl Sealed for super::Control {}
+}
+
+/// Register with a typed representation
+///
+/// This trait is sealed and implemented for the register types of this
+/// module only.
+pub trait TypedRegister: private::Sealed + Sized {
+    /// Register address.
+    const ADDRESS: u8;
+
+    /// Encode into the raw register value.
+    fn encode(&self) -> u8;
+
+    /// Decode from the raw register value.
+    ///
+    /// Reserved bits are ignored. Returns `None` if the value is invalid.
+    fn decode(value: u8) -> Option<Self>;
+}
+
+/// Register that can be written
+///
+/// This trait is sealed like [`TypedRegister`].
+pub trait WritableRegister: TypedRegister {}
+
+impl TypedRegister for Enable {
+    const ADDRESS: u8 = Register::ENABLE;
+
+    fn encode(&self) -> u8 {
+        self.bits()
+    }
+
+    fn decode(value: u8) -> Option<Self> {
+        Some(Enable::from_bits(value))
+    }
+}
+impl WritableRegister for Enable {}
+
+impl TypedRegister for Status {
+    const ADDRESS: u8 = Register::STATUS;
+
+    fn encode(&self) -> u8 {
+        let mut status = 0;
+        if self.rgbc_interrupt {
+            status |= BitFlags::RGBC_INT;
+        }
+        if self.rgbc_valid {
+            status |= BitFlags::RGBC_VALID;
+        }
+        status
+    }
+
+    fn decode(value: u8) -> Option<Self> {
+        Some(Status::from_bits(value))
+    }
+}
+
+/// RGBC timing register (ATIME)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Atime {
+    cycles: u16,
+}
+
+impl Atime {
+    /// Create for a number of integration cycles (1-256).
+    pub fn new(cycles: u16) -> Option<Self> {
+        if (1..=256).contains(&cycles) {
+            Some(Atime { cycles })
+        } else {
+            None
+        }
+    }
+
+    /// Number of integration cycles (1-256).
+    pub fn cycles(&self) -> u16 {
+        self.cycles
+    }
+}
+
+impl Default for Atime {
+    fn default() -> Self {
+        Atime { cycles: 1 }
+    }
+}
+
+impl TypedRegister for Atime {
+    const ADDRESS: u8 = Register::ATIME;
+
+    fn encode(&self) -> u8 {
+        // the value is stored as a two's complement
+        (256 - self.cycles) as u8
+    }
+
+    fn decode(value: u8) -> Option<Self> {
+        Some(Atime {
+            cycles: 256 - u16::from(value),
+        })
+    }
+}
+impl WritableRegister for Atime {}
+
+/// Wait time register (WTIME)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Wtime {
+    cycles: u16,
+}
+
+impl Wtime {
+    /// Create for a number of wait cycles (1-256).
+    pub fn new(cycles: u16) -> Option<Self> {
+        if (1..=256).contains(&cycles) {
+            Some(Wtime { cycles })
+        } else {
+            None
+        }
+    }
+
+    /// Number of wait cycles (1-256).
+    pub fn cycles(&self) -> u16 {
+        self.cycles
+    }
+}
+
+impl Default for Wtime {
+    fn default() -> Self {
+        Wtime { cycles: 1 }
+    }
+}
+
+impl TypedRegister for Wtime {
+    const ADDRESS: u8 = Register::WTIME;
+
+    fn encode(&self) -> u8 {
+        // the value is stored as a two's complement
+        (256 - self.cycles) as u8
+    }
+
+    fn decode(value: u8) -> Option<Self> {
+        Some(Wtime {
+            cycles: 256 - u16::from(value),
+        })
+    }
+}
+impl WritableRegister for Wtime {}
+
+/// Persistence register (APERS)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pers {
+    /// RGB converter interrupt persistence.
+    pub persistence: RgbCInterruptPersistence,
+}
+
+impl Default for Pers {
+    fn default() -> Self {
+        Pers {
+            persistence: RgbCInterruptPersistence::Every,
+        }
+    }
+}
+
+impl TypedRegister for Pers {
+    const ADDRESS: u8 = Register::APERS;
+
+    fn encode(&self) -> u8 {
+        self.persistence.bits()
+    }
+
+    fn decode(value: u8) -> Option<Self> {
+        let persistence = RgbCInterruptPersistence::from_bits(value & BitFlags::PERSISTENCE_MASK)?;
+        Some(Pers { persistence })
+    }
+}
+impl WritableRegister for Pers {}
+
+/// Configuration register (CONFIG)
+///
+/// Not to be confused with the complete device configuration
+/// [`Config`](crate::Config).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConfigRegister {
+    /// Wait time increased by a factor of 12 (WLONG).
+    pub wait_long: bool,
+}
+
+impl TypedRegister for ConfigRegister {
+    const ADDRESS: u8 = Register::CONFIG;
+
+    fn encode(&self) -> u8 {
+        if self.wait_long {
+            BitFlags::WLONG
+        } else {
+            0
+        }
+    }
+
+    fn decode(value: u8) -> Option<Self> {
+        Some(ConfigRegister {
+            wait_long: (value & BitFlags::WLONG) != 0,
+        })
+    }
+}
+impl WritableRegister for ConfigRegister {}
+
+/// Control register (CONTROL)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Control {
+    /// RGB converter gain.
+    pub gain: RgbCGain,
+}
+
+impl Default for Control {
+    fn default() -> Self {
+        Control {
+            gain: RgbCGain::_1x,
+        }
+    }
+}
+
+impl TypedRegister for Control {
+    const ADDRESS: u8 = Register::CONTROL;
+
+    fn encode(&self) -> u8 {
+        self.gain.bits()
+    }
+
+    fn decode(value: u8) -> Option<Self> {
+        let gain = RgbCGain::from_bits(value & BitFlags::GAIN_MASK)?;
+        Some(Control { gain })
+    }
+}
+impl WritableRegister for Control {}
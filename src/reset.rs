@@ -0,0 +1,74 @@
+//! Power-on reset detection and recovery.
+
+use crate::registers::{Atime, TypedRegister};
+#[cfg(feature = "async")]
+use crate::Tcs3472Async;
+use crate::{BitFlags, DeviceReset, EnableFlags, Error, Register, Tcs3472};
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+#[maybe_async_cfg::maybe(
+    sync(self = "Tcs3472", idents(AsyncI2c(sync = "I2c"))),
+    async(feature = "async", self = "Tcs3472Async")
+)]
+impl<I2C, E> Tcs3472<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Detect a device power-on reset and restore the configuration.
+    ///
+    /// The enable and integration time registers are read back and compared
+    /// with the shadow copy. If the device lost power, the registers revert
+    /// to their defaults and the measurements stay at zero. In that case all
+    /// known registers are written again and the values found are returned
+    /// so that the event can be logged.
+    ///
+    /// A reset can only be detected if the last known values of these
+    /// registers differ from the power-on defaults (e.g. the device was
+    /// powered on).
+    pub async fn recover_from_reset(&mut self) -> Result<Option<DeviceReset>, Error<E>> {
+        let mut data = [0; 2];
+        self.read_block(Register::ENABLE, &mut data).await?;
+        let unchanged = (Register::ENABLE..)
+            .zip(data)
+            .all(|(register, value)| self.shadow.get(register).map_or(true, |v| v == value));
+        if unchanged {
+            return Ok(None);
+        }
+        self.restore_registers().await?;
+        Ok(Some(DeviceReset {
+            enable: EnableFlags::from_bits(data[0]),
+            integration_cycles: Atime::decode(data[1]).unwrap_or_default().cycles(),
+        }))
+    }
+
+    /// Write all known registers of the shadow copy to the device.
+    ///
+    /// The enable register is written last. Note that the device needs a
+    /// warm-up time of 2.4ms after being powered on before the RGB
+    /// converter can be enabled.
+    ///
+    /// The registers are written even if the shadow copy already holds
+    /// their values. The shadow copy is kept if a write fails, so that the
+    /// configuration can still be restored later.
+    pub async fn restore_registers(&mut self) -> Result<(), Error<E>> {
+        let shadow = self.shadow;
+        for (register, value) in shadow.known() {
+            if register != Register::ENABLE {
+                self.restore_register(register, value).await?;
+            }
+        }
+        if let Some(enable) = shadow.get(Register::ENABLE) {
+            self.restore_register(Register::ENABLE, enable).await?;
+        }
+        Ok(())
+    }
+
+    async fn restore_register(&mut self, register: u8, value: u8) -> Result<(), Error<E>> {
+        self.i2c
+            .write(self.address, &[BitFlags::CMD | register, value])
+            .await
+            .map_err(Error::I2C)
+    }
+}
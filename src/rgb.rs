@@ -0,0 +1,159 @@
+//! Linear RGB and sRGB conversion with white balance.
+
+use crate::AllChannelMeasurement;
+
+/// Linear RGB color with components nominally in the range 0.0-1.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearRgb {
+    /// Red component.
+    pub red: f32,
+    /// Green component.
+    pub green: f32,
+    /// Blue component.
+    pub blue: f32,
+}
+
+/// Gamma-encoded 8-bit sRGB color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Srgb8 {
+    /// Red component.
+    pub red: u8,
+    /// Green component.
+    pub green: u8,
+    /// Blue component.
+    pub blue: u8,
+}
+
+/// Strategy for bringing colors into the 0.0-1.0 range
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamutClipping {
+    /// Clamp each component individually. This may shift the hue of
+    /// colors brighter than the white reference.
+    #[default]
+    Clamp,
+    /// Scale all components down if any of them exceeds 1.0, preserving
+    /// the hue.
+    Scale,
+    /// Always scale all components so that the largest one is 1.0.
+    /// This keeps the hue and removes the brightness information, for
+    /// example to drive an RGB LED matching the sensed color.
+    Normalize,
+}
+
+impl LinearRgb {
+    /// Apply a gamut clipping strategy.
+    pub fn clip(&self, clipping: GamutClipping) -> LinearRgb {
+        let max = self.red.max(self.green).max(self.blue);
+        let scale = match clipping {
+            GamutClipping::Scale if max > 1.0 => 1.0 / max,
+            GamutClipping::Normalize if max > 0.0 => 1.0 / max,
+            _ => 1.0,
+        };
+        LinearRgb {
+            red: (self.red * scale).clamp(0.0, 1.0),
+            green: (self.green * scale).clamp(0.0, 1.0),
+            blue: (self.blue * scale).clamp(0.0, 1.0),
+        }
+    }
+
+    /// Gamma-encode into 8-bit sRGB.
+    ///
+    /// Components out of the 0.0-1.0 range are clamped.
+    pub fn to_srgb8(&self) -> Srgb8 {
+        Srgb8 {
+            red: encode_srgb(self.red),
+            green: encode_srgb(self.green),
+            blue: encode_srgb(self.blue),
+        }
+    }
+}
+
+fn encode_srgb(value: f32) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+    let encoded = if value <= 0.003_130_8 {
+        12.92 * value
+    } else {
+        1.055 * libm::powf(value, 1.0 / 2.4) - 0.055
+    };
+    libm::roundf(encoded * 255.0) as u8
+}
+
+/// White balance for the conversion of measurements into RGB
+///
+/// Without a white reference, each channel is divided by the clear
+/// channel. With a white reference, each channel is scaled so that the
+/// white target results in equal components of 1.0. A dark reference,
+/// e.g. captured with the sensor covered, is subtracted from all
+/// measurements.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WhiteBalance {
+    white: Option<AllChannelMeasurement>,
+    dark: AllChannelMeasurement,
+    clipping: GamutClipping,
+}
+
+impl WhiteBalance {
+    /// Create a white balance without references and clamping.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the gamut clipping strategy.
+    pub fn with_clipping(mut self, clipping: GamutClipping) -> Self {
+        self.clipping = clipping;
+        self
+    }
+
+    /// Capture the white reference from a measurement of a white target.
+    ///
+    /// The measurement must be taken with the same gain and integration
+    /// time as the measurements to convert.
+    pub fn capture_white_reference(&mut self, measurement: &AllChannelMeasurement) {
+        self.white = Some(*measurement);
+    }
+
+    /// Capture the dark reference from a measurement without light.
+    ///
+    /// The measurement must be taken with the same gain and integration
+    /// time as the measurements to convert.
+    pub fn capture_dark_reference(&mut self, measurement: &AllChannelMeasurement) {
+        self.dark = *measurement;
+    }
+
+    /// Convert a measurement into linear RGB.
+    pub fn to_linear_rgb(&self, measurement: &AllChannelMeasurement) -> LinearRgb {
+        let dark = &self.dark;
+        let red = measurement.red.saturating_sub(dark.red);
+        let green = measurement.green.saturating_sub(dark.green);
+        let blue = measurement.blue.saturating_sub(dark.blue);
+        let rgb = match &self.white {
+            Some(white) => LinearRgb {
+                red: ratio(red, white.red.saturating_sub(dark.red)),
+                green: ratio(green, white.green.saturating_sub(dark.green)),
+                blue: ratio(blue, white.blue.saturating_sub(dark.blue)),
+            },
+            None => {
+                let clear = measurement.clear.saturating_sub(dark.clear);
+                LinearRgb {
+                    red: ratio(red, clear),
+                    green: ratio(green, clear),
+                    blue: ratio(blue, clear),
+                }
+            }
+        };
+        rgb.clip(self.clipping)
+    }
+
+    /// Convert a measurement into gamma-encoded 8-bit sRGB.
+    pub fn to_srgb8(&self, measurement: &AllChannelMeasurement) -> Srgb8 {
+        self.to_linear_rgb(measurement).to_srgb8()
+    }
+}
+
+fn ratio(value: u16, reference: u16) -> f32 {
+    if reference == 0 {
+        0.0
+    } else {
+        f32::from(value) / f32::from(reference)
+    }
+}
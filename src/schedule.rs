@@ -0,0 +1,191 @@
+//! Duty-cycled measurement timing with the wait feature.
+
+use crate::interface::{CYCLE_TIME_US, WAIT_LONG_FACTOR};
+use crate::registers::{self, Atime, Wtime};
+#[cfg(feature = "async")]
+use crate::Tcs3472Async;
+use crate::{BitFlags, Error, Tcs3472};
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Typical supply current during the RGBC initialization and integration.
+const ACTIVE_CURRENT_UA: f32 = 235.0;
+/// Typical supply current during the wait time.
+const WAIT_CURRENT_UA: f32 = 65.0;
+
+/// Number of cycles (1-256) closest to a duration.
+pub(crate) fn cycles_for_us(time_us: u32, cycle_time_us: u32) -> Option<u16> {
+    let cycles = (u64::from(time_us) + u64::from(cycle_time_us / 2)) / u64::from(cycle_time_us);
+    if (1..=256).contains(&cycles) {
+        Some(cycles as u16)
+    } else {
+        None
+    }
+}
+
+/// Number of wait cycles (1-256) and *wait long* setting closest to a
+/// duration.
+///
+/// *Wait long* is only used if it gets closer to the duration.
+pub(crate) fn wait_for_us(time_us: u32) -> Option<(u16, bool)> {
+    let short = cycles_for_us(time_us, CYCLE_TIME_US);
+    let long = cycles_for_us(time_us, CYCLE_TIME_US * WAIT_LONG_FACTOR);
+    let error =
+        |cycles: u16, factor: u32| (u32::from(cycles) * CYCLE_TIME_US * factor).abs_diff(time_us);
+    match (short, long) {
+        (Some(short), Some(long)) if error(long, WAIT_LONG_FACTOR) < error(short, 1) => {
+            Some((long, true))
+        }
+        (Some(short), _) => Some((short, false)),
+        (None, Some(long)) => Some((long, true)),
+        (None, None) => None,
+    }
+}
+
+/// Wait time for a number of wait cycles and *wait long* setting.
+pub(crate) fn wait_time_us(cycles: u16, wait_long: bool) -> u32 {
+    let factor = if wait_long { WAIT_LONG_FACTOR } else { 1 };
+    u32::from(cycles) * CYCLE_TIME_US * factor
+}
+
+/// Errors computing a duty cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DutyCycleError {
+    /// The integration time is out of range (2.4ms-614.4ms).
+    IntegrationTime,
+    /// The period cannot be achieved with the integration time.
+    Period,
+}
+
+impl<E> From<DutyCycleError> for Error<E> {
+    fn from(_: DutyCycleError) -> Self {
+        Error::InvalidInputData
+    }
+}
+
+/// Duty-cycled measurement timing
+///
+/// With the RGB converter and the wait feature enabled, the device
+/// repeats a cycle of wait time, 2.4ms RGBC initialization and
+/// integration time. In the wait time the supply current is much lower,
+/// so a long period between measurements saves power.
+///
+/// [`DutyCycle::new()`] computes the integration cycles (ATIME), wait
+/// cycles (WTIME) and *wait long* setting (WLONG) coming closest to a
+/// desired period and integration time. The achieved period can be
+/// checked with [`period_us()`](DutyCycle::period_us).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DutyCycle {
+    integration_cycles: u16,
+    wait_cycles: u16,
+    wait_long: bool,
+}
+
+impl DutyCycle {
+    /// Compute the timing for a period and an integration time in
+    /// microseconds.
+    ///
+    /// The integration time is rounded to the nearest number of cycles.
+    /// If the period is at most half a cycle longer than the RGBC
+    /// initialization plus the integration time, the wait feature is not
+    /// used. Otherwise the wait time closest to the rest of the period is
+    /// selected.
+    ///
+    /// Returns `DutyCycleError::Period` if the period is shorter than the
+    /// RGBC initialization plus the integration time or longer than the
+    /// longest wait time allows.
+    pub fn new(period_us: u32, integration_time_us: u32) -> Result<Self, DutyCycleError> {
+        let integration_cycles = cycles_for_us(integration_time_us, CYCLE_TIME_US)
+            .ok_or(DutyCycleError::IntegrationTime)?;
+        let active_us = (u32::from(integration_cycles) + 1) * CYCLE_TIME_US;
+        if period_us.saturating_add(CYCLE_TIME_US / 2) < active_us {
+            return Err(DutyCycleError::Period);
+        }
+        let (wait_cycles, wait_long) = if period_us < active_us + CYCLE_TIME_US / 2 {
+            (0, false)
+        } else {
+            wait_for_us(period_us - active_us).ok_or(DutyCycleError::Period)?
+        };
+        Ok(DutyCycle {
+            integration_cycles,
+            wait_cycles,
+            wait_long,
+        })
+    }
+
+    /// Number of integration cycles (1-256).
+    pub fn integration_cycles(&self) -> u16 {
+        self.integration_cycles
+    }
+
+    /// Number of wait cycles (1-256) or 0 if the wait feature is not used.
+    pub fn wait_cycles(&self) -> u16 {
+        self.wait_cycles
+    }
+
+    /// Whether the *wait long* setting is used.
+    pub fn wait_long(&self) -> bool {
+        self.wait_long
+    }
+
+    /// Integration time in microseconds.
+    pub fn integration_time_us(&self) -> u32 {
+        u32::from(self.integration_cycles) * CYCLE_TIME_US
+    }
+
+    /// Wait time in microseconds.
+    pub fn wait_time_us(&self) -> u32 {
+        wait_time_us(self.wait_cycles, self.wait_long)
+    }
+
+    /// Achieved period in microseconds.
+    ///
+    /// This includes the wait time, the 2.4ms RGBC initialization and the
+    /// integration time.
+    pub fn period_us(&self) -> u32 {
+        self.wait_time_us() + CYCLE_TIME_US + self.integration_time_us()
+    }
+
+    /// Estimate the average supply current in µA.
+    ///
+    /// This uses the typical supply currents of the datasheet: 235µA
+    /// while active and 65µA during the wait time.
+    pub fn average_current_ua(&self) -> f32 {
+        let active_us = (CYCLE_TIME_US + self.integration_time_us()) as f32;
+        let wait_us = self.wait_time_us() as f32;
+        (ACTIVE_CURRENT_UA * active_us + WAIT_CURRENT_UA * wait_us) / self.period_us() as f32
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(self = "Tcs3472", idents(AsyncI2c(sync = "I2c"))),
+    async(feature = "async", self = "Tcs3472Async")
+)]
+impl<I2C, E> Tcs3472<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Configure the integration time and the wait feature for a duty cycle.
+    ///
+    /// This sets the number of integration cycles and, if the duty cycle
+    /// uses it, the number of wait cycles and the *wait long* setting and
+    /// then enables or disables the wait feature. The other enable
+    /// settings are preserved, so the measurements start once the device
+    /// is powered on and the RGB converter enabled.
+    pub async fn apply_duty_cycle(&mut self, duty_cycle: &DutyCycle) -> Result<(), Error<E>> {
+        let atime = Atime::new(duty_cycle.integration_cycles).ok_or(Error::InvalidInputData)?;
+        self.write_typed_register(atime).await?;
+        let enable = self.shadow.enable();
+        if duty_cycle.wait_cycles == 0 {
+            return self.write_enable(enable & !BitFlags::WAIT_EN).await;
+        }
+        let wtime = Wtime::new(duty_cycle.wait_cycles).ok_or(Error::InvalidInputData)?;
+        self.write_typed_register(wtime).await?;
+        self.write_typed_register(registers::ConfigRegister {
+            wait_long: duty_cycle.wait_long,
+        })
+        .await?;
+        self.write_enable(enable | BitFlags::WAIT_EN).await
+    }
+}
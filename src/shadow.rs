@@ -0,0 +1,169 @@
+//! Shadow copy of the writable registers.
+
+use crate::registers::{Atime, TypedRegister};
+#[cfg(feature = "async")]
+use crate::Tcs3472Async;
+use crate::{Config, Error, Register, Tcs3472};
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Number of registers from ENABLE up to and including CONTROL.
+pub(crate) const SHADOW_LEN: usize = 16;
+
+/// Writable registers: ENABLE, ATIME, WTIME, AILTL-AIHTH, APERS, CONFIG
+/// and CONTROL.
+const WRITABLE: u16 = 0b1011_0000_1111_1011;
+
+/// Shadow copy of the writable registers
+///
+/// The values start with the power-on defaults. A register is *known*
+/// once it has been written or read successfully and becomes unknown
+/// again if a write to it fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Shadow {
+    values: [u8; SHADOW_LEN],
+    known: u16,
+}
+
+impl Default for Shadow {
+    fn default() -> Self {
+        let mut values = [0; SHADOW_LEN];
+        values[usize::from(Register::ATIME)] = 0xFF;
+        values[usize::from(Register::WTIME)] = 0xFF;
+        Shadow { values, known: 0 }
+    }
+}
+
+pub(crate) fn is_writable(register: u8) -> bool {
+    usize::from(register) < SHADOW_LEN && (WRITABLE & (1 << register)) != 0
+}
+
+impl Shadow {
+    /// Enable register value, assuming the power-on default if unknown.
+    pub(crate) fn enable(&self) -> u8 {
+        self.values[usize::from(Register::ENABLE)]
+    }
+
+    /// Number of integration cycles, assuming the power-on default if
+    /// unknown.
+    pub(crate) fn integration_cycles(&self) -> u16 {
+        Atime::decode(self.values[usize::from(Register::ATIME)])
+            .unwrap_or_default()
+            .cycles()
+    }
+
+    pub(crate) fn get(&self, register: u8) -> Option<u8> {
+        if is_writable(register) && (self.known & (1 << register)) != 0 {
+            Some(self.values[usize::from(register)])
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn all_known(&self) -> bool {
+        self.known & WRITABLE == WRITABLE
+    }
+
+    /// Known registers and their values in address order.
+    pub(crate) fn known(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+        (0..SHADOW_LEN as u8).filter_map(|register| Some((register, self.get(register)?)))
+    }
+
+    pub(crate) fn values(&self) -> &[u8; SHADOW_LEN] {
+        &self.values
+    }
+
+    /// Whether all the registers are known to hold these values already.
+    pub(crate) fn contains(&self, first_register: u8, values: &[u8]) -> bool {
+        (first_register..)
+            .zip(values)
+            .all(|(register, value)| self.get(register) == Some(*value))
+    }
+
+    pub(crate) fn store(&mut self, first_register: u8, values: &[u8]) {
+        for (register, value) in (first_register..).zip(values) {
+            if is_writable(register) {
+                self.values[usize::from(register)] = *value;
+                self.known |= 1 << register;
+            }
+        }
+    }
+
+    pub(crate) fn invalidate(&mut self, first_register: u8, count: usize) {
+        for register in (first_register..).take(count) {
+            if is_writable(register) {
+                self.known &= !(1 << register);
+            }
+        }
+    }
+
+    /// Whether all known registers match the values read from the device.
+    pub(crate) fn matches(&self, data: &[u8; SHADOW_LEN]) -> bool {
+        (0..)
+            .zip(data)
+            .all(|(register, value)| self.get(register).map_or(true, |cached| cached == *value))
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(self = "Tcs3472"),
+    async(feature = "async", self = "Tcs3472Async")
+)]
+impl<I2C> Tcs3472<I2C> {
+    /// Get the value of a writable register from the shadow copy.
+    ///
+    /// Returns `None` if the value is not known, i.e. it has not been
+    /// written or read yet, or the register is not writable.
+    pub fn cached_register(&self, register: u8) -> Option<u8> {
+        self.shadow.get(register)
+    }
+
+    /// Get a typed register from the shadow copy.
+    ///
+    /// Returns `None` if the value is not known or cannot be decoded.
+    pub fn cached_typed_register<R: TypedRegister>(&self) -> Option<R> {
+        self.shadow.get(R::ADDRESS).and_then(R::decode)
+    }
+
+    /// Get the complete configuration from the shadow copy.
+    ///
+    /// Returns `None` unless all registers are known, e.g. after
+    /// [`apply_config()`](#method.apply_config) or
+    /// [`sync_from_device()`](#method.sync_from_device).
+    pub fn cached_config(&self) -> Option<Config> {
+        if self.shadow.all_known() {
+            Config::from_registers(self.shadow.values())
+        } else {
+            None
+        }
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(self = "Tcs3472", idents(AsyncI2c(sync = "I2c"))),
+    async(feature = "async", self = "Tcs3472Async")
+)]
+impl<I2C, E> Tcs3472<I2C>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Read all writable registers into the shadow copy.
+    ///
+    /// All registers are read at once and are known afterwards.
+    pub async fn sync_from_device(&mut self) -> Result<(), Error<E>> {
+        let mut data = [0; SHADOW_LEN];
+        self.read_registers(Register::ENABLE, &mut data).await
+    }
+
+    /// Check whether the device registers match the shadow copy.
+    ///
+    /// All registers are read at once and compared with the known values
+    /// of the shadow copy, which is left unchanged. A mismatch indicates
+    /// that the device lost its configuration, e.g. after a power glitch.
+    pub async fn verify(&mut self) -> Result<bool, Error<E>> {
+        let mut data = [0; SHADOW_LEN];
+        self.read_block(Register::ENABLE, &mut data).await?;
+        Ok(self.shadow.matches(&data))
+    }
+}
@@ -0,0 +1,484 @@
+//! Behavioural software simulator of the TCS3472.
+//!
+//! The [`Simulator`] models the device at register level so that
+//! application logic can be tested without hardware:
+//! - Command register with repeated byte, auto-increment and the RGBC
+//!   interrupt clear special function.
+//! - All registers, including the read-only ID, status and data registers.
+//! - PON/AEN state machine with the wait time (including *wait long*),
+//!   the 2.4ms initialization and the RGBC integration of each cycle.
+//! - Channel counts for the configured gain and integration time,
+//!   saturating at the full-scale count.
+//! - AVALID and AINT status flags, including the interrupt thresholds and
+//!   persistence.
+//!
+//! Time is simulated. It advances through the delay provider returned by
+//! [`Simulator::delay()`] or explicitly with [`Simulator::advance_us()`].
+//! The light reaching the sensor is given by a [`Scene`].
+//!
+//! ```
+//! use tcs3472::sim::{Light, Simulator};
+//! use tcs3472::Tcs3472;
+//!
+//! let light = Light { red: 100.0, green: 200.0, blue: 50.0, clear: 400.0 };
+//! let sim = Simulator::new(light);
+//! let mut sensor = Tcs3472::new(sim.i2c());
+//! let measurement = sensor.measure(&mut sim.delay(), true).unwrap();
+//! assert_eq!(400, measurement.clear);
+//! ```
+
+use crate::interface::{CYCLE_TIME_US, WAIT_LONG_FACTOR};
+use crate::registers::{Atime, Control, Pers, TypedRegister, Wtime};
+use crate::shadow::is_writable;
+use crate::{BitFlags, DeviceVariant, Register, RgbCInterruptPersistence};
+use core::cell::RefCell;
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+
+const CYCLE_TIME_NS: u64 = CYCLE_TIME_US as u64 * 1000;
+const REGISTER_COUNT: usize = 32;
+
+/// Light reaching the sensor
+///
+/// Each channel is given in counts per integration cycle at 1x gain.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Light {
+    /// Red channel.
+    pub red: f32,
+    /// Green channel.
+    pub green: f32,
+    /// Blue channel.
+    pub blue: f32,
+    /// Clear (unfiltered) channel.
+    pub clear: f32,
+}
+
+/// Light reaching the sensor over time
+///
+/// This is implemented for a constant [`Light`], for a [`Script`] and for
+/// closures taking the simulated time in microseconds.
+pub trait Scene {
+    /// Light at a point in simulated time in microseconds.
+    fn light(&self, time_us: u64) -> Light;
+}
+
+impl Scene for Light {
+    fn light(&self, _time_us: u64) -> Light {
+        *self
+    }
+}
+
+impl<F: Fn(u64) -> Light> Scene for F {
+    fn light(&self, time_us: u64) -> Light {
+        self(time_us)
+    }
+}
+
+/// Scripted scene switching between lights at given points in time
+///
+/// Each step consists of the time in microseconds from which on the light
+/// applies. The steps must be sorted by time. Before the first step, the
+/// scene is dark.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Script<'a> {
+    steps: &'a [(u64, Light)],
+}
+
+impl<'a> Script<'a> {
+    /// Create a script from its steps.
+    pub const fn new(steps: &'a [(u64, Light)]) -> Self {
+        Script { steps }
+    }
+}
+
+impl Scene for Script<'_> {
+    fn light(&self, time_us: u64) -> Light {
+        self.steps
+            .iter()
+            .take_while(|(start, _)| *start <= time_us)
+            .last()
+            .map(|(_, light)| *light)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Sleep,
+    Idle,
+    Init { end: u64 },
+    Integration { start: u64, end: u64, cycles: u16 },
+    Wait { end: u64 },
+}
+
+#[derive(Debug)]
+struct State<S> {
+    scene: S,
+    address: u8,
+    registers: [u8; REGISTER_COUNT],
+    pointer: u8,
+    auto_increment: bool,
+    time: u64,
+    phase: Phase,
+    out_of_range_count: u8,
+}
+
+impl<S: Scene> State<S> {
+    fn reset(&mut self, id: u8) {
+        self.registers = [0; REGISTER_COUNT];
+        self.registers[usize::from(Register::ATIME)] = 0xFF;
+        self.registers[usize::from(Register::WTIME)] = 0xFF;
+        self.registers[usize::from(Register::ID)] = id;
+        self.pointer = 0;
+        self.auto_increment = false;
+        self.phase = Phase::Sleep;
+        self.out_of_range_count = 0;
+    }
+
+    fn reg(&self, register: u8) -> u8 {
+        self.registers[usize::from(register)]
+    }
+
+    fn decode<R: TypedRegister + Default>(&self) -> R {
+        R::decode(self.reg(R::ADDRESS)).unwrap_or_default()
+    }
+
+    fn advance_to(&mut self, target: u64) {
+        loop {
+            match self.phase {
+                Phase::Init { end } if end <= target => {
+                    self.time = end;
+                    self.start_integration();
+                }
+                Phase::Wait { end } if end <= target => {
+                    self.time = end;
+                    self.start_init();
+                }
+                Phase::Integration { start, end, cycles } if end <= target => {
+                    self.time = end;
+                    self.complete_integration(start, cycles);
+                    self.start_wait();
+                }
+                _ => break,
+            }
+        }
+        self.time = target;
+    }
+
+    fn start_init(&mut self) {
+        self.phase = Phase::Init {
+            end: self.time + CYCLE_TIME_NS,
+        };
+    }
+
+    fn start_integration(&mut self) {
+        let cycles = self.decode::<Atime>().cycles();
+        self.phase = Phase::Integration {
+            start: self.time,
+            end: self.time + u64::from(cycles) * CYCLE_TIME_NS,
+            cycles,
+        };
+    }
+
+    fn start_wait(&mut self) {
+        if self.reg(Register::ENABLE) & BitFlags::WAIT_EN == 0 {
+            self.start_init();
+            return;
+        }
+        let cycles = u64::from(self.decode::<Wtime>().cycles());
+        let factor = if self.reg(Register::CONFIG) & BitFlags::WLONG != 0 {
+            u64::from(WAIT_LONG_FACTOR)
+        } else {
+            1
+        };
+        self.phase = Phase::Wait {
+            end: self.time + cycles * factor * CYCLE_TIME_NS,
+        };
+    }
+
+    fn complete_integration(&mut self, start: u64, cycles: u16) {
+        let light = self.scene.light((start + self.time) / 2 / 1000);
+        let gain = self.decode::<Control>().gain.multiplier();
+        let full_scale = crate::full_scale_count(cycles);
+        let count = |value: f32| {
+            let count = libm::roundf(value * f32::from(cycles) * f32::from(gain));
+            count.clamp(0.0, f32::from(full_scale)) as u16
+        };
+        let clear = count(light.clear);
+        let channels = [
+            (Register::CDATA, clear),
+            (Register::RDATA, count(light.red)),
+            (Register::GDATA, count(light.green)),
+            (Register::BDATA, count(light.blue)),
+        ];
+        for (register, value) in channels {
+            let [low, high] = value.to_le_bytes();
+            self.registers[usize::from(register)] = low;
+            self.registers[usize::from(register) + 1] = high;
+        }
+        self.registers[usize::from(Register::STATUS)] |= BitFlags::RGBC_VALID;
+        self.update_interrupt(clear);
+    }
+
+    fn update_interrupt(&mut self, clear: u16) {
+        if self.reg(Register::ENABLE) & BitFlags::RGBC_INT_EN == 0 {
+            return;
+        }
+        let threshold =
+            |register: u8| u16::from_le_bytes([self.reg(register), self.reg(register + 1)]);
+        let out_of_range = clear < threshold(Register::AILTL) || clear > threshold(Register::AIHTL);
+        let required = match self.decode::<Pers>().persistence {
+            RgbCInterruptPersistence::Every => 0,
+            RgbCInterruptPersistence::_1 => 1,
+            RgbCInterruptPersistence::_2 => 2,
+            RgbCInterruptPersistence::_3 => 3,
+            // from 5 on, the values increase in steps of 5
+            persistence => (persistence.bits() - 3) * 5,
+        };
+        if out_of_range {
+            self.out_of_range_count = self.out_of_range_count.saturating_add(1);
+        } else {
+            self.out_of_range_count = 0;
+        }
+        if required == 0 || self.out_of_range_count >= required {
+            self.registers[usize::from(Register::STATUS)] |= BitFlags::RGBC_INT;
+        }
+    }
+
+    fn write_enable(&mut self, enable: u8) {
+        self.registers[usize::from(Register::ENABLE)] = enable;
+        let power_on = enable & BitFlags::POWER_ON != 0;
+        let rgbc = enable & BitFlags::RGBC_EN != 0;
+        match (power_on, rgbc, self.phase) {
+            (false, _, _) => self.phase = Phase::Sleep,
+            (true, false, _) => self.phase = Phase::Idle,
+            (true, true, Phase::Sleep | Phase::Idle) => {
+                self.registers[usize::from(Register::STATUS)] &= !BitFlags::RGBC_VALID;
+                self.start_init();
+            }
+            (true, true, _) => {}
+        }
+    }
+
+    fn command(&mut self, command: u8) -> Result<(), ErrorKind> {
+        if command & BitFlags::CMD == 0 {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data));
+        }
+        let address = command & BitFlags::CMD_ADDR;
+        match command & BitFlags::CMD_SPECIAL_FN {
+            0 => self.auto_increment = false,
+            BitFlags::CMD_AUTO_INC => self.auto_increment = true,
+            BitFlags::CMD_SPECIAL_FN => {
+                if address == BitFlags::RGBC_INT_CLEAR {
+                    self.registers[usize::from(Register::STATUS)] &= !BitFlags::RGBC_INT;
+                    self.out_of_range_count = 0;
+                }
+                return Ok(());
+            }
+            _ => return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data)),
+        }
+        self.pointer = address;
+        Ok(())
+    }
+
+    fn next_pointer(&mut self) -> u8 {
+        let pointer = self.pointer;
+        if self.auto_increment {
+            self.pointer = (pointer + 1) & BitFlags::CMD_ADDR;
+        }
+        pointer
+    }
+
+    fn write(&mut self, value: u8) {
+        let register = self.next_pointer();
+        if register == Register::ENABLE {
+            self.write_enable(value);
+        } else if is_writable(register) {
+            self.registers[usize::from(register)] = value;
+        }
+    }
+
+    fn read(&mut self) -> u8 {
+        let register = self.next_pointer();
+        self.reg(register)
+    }
+}
+
+/// Behavioural simulator of a TCS3472 device
+///
+/// See the [module documentation](self) for what is modeled. The simulator
+/// is shared through the handles returned by [`i2c()`](Self::i2c) and
+/// [`delay()`](Self::delay).
+#[derive(Debug)]
+pub struct Simulator<S> {
+    state: RefCell<State<S>>,
+    variant: DeviceVariant,
+}
+
+impl<S: Scene> Simulator<S> {
+    /// Create a simulated TCS34725 in the power-on state.
+    pub fn new(scene: S) -> Self {
+        Self::new_with_variant(DeviceVariant::Tcs34725, scene)
+    }
+
+    /// Create a simulated device variant in the power-on state.
+    pub fn new_with_variant(variant: DeviceVariant, scene: S) -> Self {
+        let mut state = State {
+            scene,
+            address: variant.address(),
+            registers: [0; REGISTER_COUNT],
+            pointer: 0,
+            auto_increment: false,
+            time: 0,
+            phase: Phase::Sleep,
+            out_of_range_count: 0,
+        };
+        state.reset(variant.id());
+        Simulator {
+            state: RefCell::new(state),
+            variant,
+        }
+    }
+
+    /// Get an I²C bus handle connected to the simulated device.
+    pub fn i2c(&self) -> SimI2c<'_, S> {
+        SimI2c { sim: self }
+    }
+
+    /// Get a delay provider advancing the simulated time.
+    pub fn delay(&self) -> SimDelay<'_, S> {
+        SimDelay { sim: self }
+    }
+
+    /// Advance the simulated time.
+    pub fn advance_us(&self, us: u64) {
+        let mut state = self.state.borrow_mut();
+        let target = state.time + us * 1000;
+        state.advance_to(target);
+    }
+
+    /// Simulated time in microseconds.
+    pub fn time_us(&self) -> u64 {
+        self.state.borrow().time / 1000
+    }
+
+    /// Current value of a register.
+    pub fn register(&self, register: u8) -> u8 {
+        self.state.borrow().registers[usize::from(register & BitFlags::CMD_ADDR)]
+    }
+
+    /// Whether the INT pin is asserted (low).
+    pub fn is_interrupt_asserted(&self) -> bool {
+        self.register(Register::STATUS) & BitFlags::RGBC_INT != 0
+    }
+
+    /// Replace the scene.
+    pub fn set_scene(&self, scene: S) {
+        self.state.borrow_mut().scene = scene;
+    }
+
+    /// Simulate a power-on reset, e.g. after a supply glitch.
+    ///
+    /// All registers revert to their defaults.
+    pub fn power_on_reset(&self) {
+        self.state.borrow_mut().reset(self.variant.id());
+    }
+
+    fn transaction(&self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), ErrorKind> {
+        let mut state = self.state.borrow_mut();
+        if address != state.address {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+        // The first byte after a (repeated) start condition is a command.
+        let mut command_expected = true;
+        for operation in operations {
+            match operation {
+                Operation::Write(data) => {
+                    for value in data.iter() {
+                        if command_expected {
+                            state.command(*value)?;
+                            command_expected = false;
+                        } else {
+                            state.write(*value);
+                        }
+                    }
+                }
+                Operation::Read(buffer) => {
+                    for value in buffer.iter_mut() {
+                        *value = state.read();
+                    }
+                    command_expected = true;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// I²C bus handle connected to a [`Simulator`]
+#[derive(Debug)]
+pub struct SimI2c<'a, S> {
+    sim: &'a Simulator<S>,
+}
+
+impl<S> Clone for SimI2c<'_, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for SimI2c<'_, S> {}
+
+impl<S> ErrorType for SimI2c<'_, S> {
+    type Error = ErrorKind;
+}
+
+impl<S: Scene> I2c for SimI2c<'_, S> {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.sim.transaction(address, operations)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S: Scene> embedded_hal_async::i2c::I2c for SimI2c<'_, S> {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.sim.transaction(address, operations)
+    }
+}
+
+/// Delay provider advancing the time of a [`Simulator`]
+#[derive(Debug)]
+pub struct SimDelay<'a, S> {
+    sim: &'a Simulator<S>,
+}
+
+impl<S> Clone for SimDelay<'_, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for SimDelay<'_, S> {}
+
+impl<S: Scene> DelayNs for SimDelay<'_, S> {
+    fn delay_ns(&mut self, ns: u32) {
+        let mut state = self.sim.state.borrow_mut();
+        let target = state.time + u64::from(ns);
+        state.advance_to(target);
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S: Scene> embedded_hal_async::delay::DelayNs for SimDelay<'_, S> {
+    async fn delay_ns(&mut self, ns: u32) {
+        DelayNs::delay_ns(self, ns)
+    }
+}
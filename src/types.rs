@@ -1,3 +1,10 @@
+use crate::interface::{BitFlags, DeviceId, DEVICE_ADDRESS, DEVICE_ADDRESS_ALT};
+
+/// Maximum count of the RGBC channels for a number of integration cycles.
+pub(crate) fn full_scale_count(integration_cycles: u16) -> u16 {
+    (u32::from(integration_cycles) * 1024).min(u32::from(u16::MAX)) as u16
+}
+
 /// All possible errors in this crate
 #[derive(Debug)]
 pub enum Error<E> {
@@ -5,6 +12,60 @@ pub enum Error<E> {
     I2C(E),
     /// Invalid input data provided.
     InvalidInputData,
+    /// Invalid value read from a device register.
+    InvalidRegisterValue,
+    /// The device ID does not correspond to the expected device variant.
+    UnexpectedDeviceId,
+    /// Timed out waiting for the device.
+    Timeout,
+    /// Error waiting on the interrupt pin.
+    ///
+    /// Contains the kind of the pin error, since `Error` is only generic
+    /// over the I²C bus error type.
+    Pin(embedded_hal::digital::ErrorKind),
+}
+
+/// Device variant
+///
+/// The variants differ in their I²C address and I²C bus voltage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeviceVariant {
+    /// TCS34721: address 0x39, I²C bus voltage V<sub>DD</sub>.
+    Tcs34721,
+    /// TCS34723: address 0x39, I²C bus voltage 1.8V.
+    Tcs34723,
+    /// TCS34725: address 0x29, I²C bus voltage V<sub>DD</sub>.
+    Tcs34725,
+    /// TCS34727: address 0x29, I²C bus voltage 1.8V.
+    Tcs34727,
+}
+
+impl DeviceVariant {
+    /// I²C address of the device variant.
+    pub fn address(self) -> u8 {
+        match self {
+            DeviceVariant::Tcs34721 | DeviceVariant::Tcs34723 => DEVICE_ADDRESS_ALT,
+            DeviceVariant::Tcs34725 | DeviceVariant::Tcs34727 => DEVICE_ADDRESS,
+        }
+    }
+
+    /// Value of the ID register of the device variant.
+    pub fn id(self) -> u8 {
+        match self {
+            DeviceVariant::Tcs34721 | DeviceVariant::Tcs34725 => DeviceId::TCS34721_5,
+            DeviceVariant::Tcs34723 | DeviceVariant::Tcs34727 => DeviceId::TCS34723_7,
+        }
+    }
+
+    pub(crate) fn from_id_and_address(id: u8, address: u8) -> Option<Self> {
+        match (id, address) {
+            (DeviceId::TCS34721_5, DEVICE_ADDRESS_ALT) => Some(DeviceVariant::Tcs34721),
+            (DeviceId::TCS34723_7, DEVICE_ADDRESS_ALT) => Some(DeviceVariant::Tcs34723),
+            (DeviceId::TCS34721_5, DEVICE_ADDRESS) => Some(DeviceVariant::Tcs34725),
+            (DeviceId::TCS34723_7, DEVICE_ADDRESS) => Some(DeviceVariant::Tcs34727),
+            _ => None,
+        }
+    }
 }
 
 /// RGB converter gain
@@ -20,6 +81,37 @@ pub enum RgbCGain {
     _60x,
 }
 
+impl RgbCGain {
+    pub(crate) fn bits(self) -> u8 {
+        // Register field: AGAIN
+        match self {
+            RgbCGain::_1x => 0,
+            RgbCGain::_4x => 1,
+            RgbCGain::_16x => 2,
+            RgbCGain::_60x => 3,
+        }
+    }
+
+    pub(crate) fn multiplier(self) -> u16 {
+        match self {
+            RgbCGain::_1x => 1,
+            RgbCGain::_4x => 4,
+            RgbCGain::_16x => 16,
+            RgbCGain::_60x => 60,
+        }
+    }
+
+    pub(crate) fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0 => Some(RgbCGain::_1x),
+            1 => Some(RgbCGain::_4x),
+            2 => Some(RgbCGain::_16x),
+            3 => Some(RgbCGain::_60x),
+            _ => None,
+        }
+    }
+}
+
 /// RGB converter interrupt persistence
 ///
 /// This controls the RGB converter interrupt generation rate.
@@ -59,8 +151,54 @@ pub enum RgbCInterruptPersistence {
     _60,
 }
 
-/// Result of measurement of all channels
+impl RgbCInterruptPersistence {
+    const ALL: [RgbCInterruptPersistence; 16] = [
+        Self::Every,
+        Self::_1,
+        Self::_2,
+        Self::_3,
+        Self::_5,
+        Self::_10,
+        Self::_15,
+        Self::_20,
+        Self::_25,
+        Self::_30,
+        Self::_35,
+        Self::_40,
+        Self::_45,
+        Self::_50,
+        Self::_55,
+        Self::_60,
+    ];
+
+    pub(crate) fn bits(self) -> u8 {
+        // Register field: APERS. The variants are declared in register order.
+        self as u8
+    }
+
+    pub(crate) fn from_bits(bits: u8) -> Option<Self> {
+        Self::ALL.get(usize::from(bits)).copied()
+    }
+}
+
+/// RGB converter interrupt trigger condition
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RgbCInterruptTrigger {
+    /// Every RGBC cycle generates an interrupt.
+    EveryCycle,
+    /// Clear channel values out of the thresholds generate an interrupt.
+    Thresholds {
+        /// Clear channel low threshold.
+        low: u16,
+        /// Clear channel high threshold.
+        high: u16,
+        /// Number of consecutive values out of range needed.
+        persistence: RgbCInterruptPersistence,
+    },
+}
+
+/// Result of measurement of all channels
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct AllChannelMeasurement {
     /// Red channel measurement.
     pub red: u16,
@@ -71,3 +209,139 @@ pub struct AllChannelMeasurement {
     /// Clear (unfiltered) channel measurement.
     pub clear: u16,
 }
+
+/// RGB converter status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Status {
+    /// RGBC clear channel interrupt asserted (AINT).
+    pub rgbc_interrupt: bool,
+    /// RGBC channels have completed an integration cycle (AVALID).
+    pub rgbc_valid: bool,
+}
+
+/// Enable register flags
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EnableFlags {
+    /// Device powered on (PON).
+    pub power_on: bool,
+    /// RGB converter enabled (AEN).
+    pub rgbc: bool,
+    /// Wait feature enabled (WEN).
+    pub wait: bool,
+    /// RGB converter interrupt generation enabled (AIEN).
+    pub rgbc_interrupts: bool,
+}
+
+impl EnableFlags {
+    pub(crate) fn from_bits(enable: u8) -> Self {
+        EnableFlags {
+            power_on: (enable & BitFlags::POWER_ON) != 0,
+            rgbc: (enable & BitFlags::RGBC_EN) != 0,
+            wait: (enable & BitFlags::WAIT_EN) != 0,
+            rgbc_interrupts: (enable & BitFlags::RGBC_INT_EN) != 0,
+        }
+    }
+
+    pub(crate) fn bits(self) -> u8 {
+        let mut enable = 0;
+        if self.power_on {
+            enable |= BitFlags::POWER_ON;
+        }
+        if self.rgbc {
+            enable |= BitFlags::RGBC_EN;
+        }
+        if self.wait {
+            enable |= BitFlags::WAIT_EN;
+        }
+        if self.rgbc_interrupts {
+            enable |= BitFlags::RGBC_INT_EN;
+        }
+        enable
+    }
+}
+
+impl Status {
+    pub(crate) fn from_bits(status: u8) -> Self {
+        Status {
+            rgbc_interrupt: (status & BitFlags::RGBC_INT) != 0,
+            rgbc_valid: (status & BitFlags::RGBC_VALID) != 0,
+        }
+    }
+}
+
+/// Device power-on reset detected and recovered from
+///
+/// Contains the register contents found in the device before the
+/// configuration was restored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeviceReset {
+    /// Enable register flags found in the device.
+    pub enable: EnableFlags,
+    /// Number of integration cycles found in the device.
+    pub integration_cycles: u16,
+}
+
+/// Saturation of a channel measurement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Saturation {
+    /// The measurement is not saturated.
+    None,
+    /// The measurement is above 75% of the full-scale count with an
+    /// integration time below 64 cycles and may be affected by analog
+    /// (ripple) saturation.
+    Analog,
+    /// The measurement reached the full-scale count.
+    Digital,
+}
+
+impl Saturation {
+    /// Classify a channel value for a full-scale count.
+    ///
+    /// Analog saturation is only considered for integration times below
+    /// 64 cycles (153.6ms), i.e. full-scale counts below 65535. With longer
+    /// integration times the counter saturates digitally first (see
+    /// application note DN40).
+    pub fn classify(value: u16, full_scale: u16) -> Self {
+        if value >= full_scale {
+            Saturation::Digital
+        } else if full_scale < u16::MAX && u32::from(value) * 4 > u32::from(full_scale) * 3 {
+            Saturation::Analog
+        } else {
+            Saturation::None
+        }
+    }
+}
+
+/// Saturation of all channels of a measurement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChannelSaturation {
+    /// Red channel saturation.
+    pub red: Saturation,
+    /// Green channel saturation.
+    pub green: Saturation,
+    /// Blue channel saturation.
+    pub blue: Saturation,
+    /// Clear (unfiltered) channel saturation.
+    pub clear: Saturation,
+}
+
+impl ChannelSaturation {
+    /// Most severe saturation among all channels.
+    pub fn worst(&self) -> Saturation {
+        self.red.max(self.green).max(self.blue).max(self.clear)
+    }
+}
+
+impl AllChannelMeasurement {
+    /// Classify the saturation of all channels for a full-scale count.
+    ///
+    /// See [`Tcs3472::full_scale_count()`](crate::Tcs3472::full_scale_count).
+    pub fn saturation(&self, full_scale: u16) -> ChannelSaturation {
+        ChannelSaturation {
+            red: Saturation::classify(self.red, full_scale),
+            green: Saturation::classify(self.green, full_scale),
+            blue: Saturation::classify(self.blue, full_scale),
+            clear: Saturation::classify(self.clear, full_scale),
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! Type-state front end enforcing the power and enable sequence.
+
+#[cfg(feature = "async")]
+use crate::Tcs3472Async;
+use crate::{AllChannelMeasurement, BitFlags, ChannelSaturation, Error, RgbCGain, Status, Tcs3472};
+use core::marker::PhantomData;
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+/// Device state marker types
+pub mod state {
+    /// Device powered off (sleep). (Type-state marker, do not use directly)
+    #[derive(Debug)]
+    pub struct Off(());
+    /// Device powered on with the RGB converter disabled.
+    /// (Type-state marker, do not use directly)
+    #[derive(Debug)]
+    pub struct Idle(());
+    /// Device powered on with the RGB converter enabled.
+    /// (Type-state marker, do not use directly)
+    #[derive(Debug)]
+    pub struct Active(());
+}
+use self::state::{Active, Idle, Off};
+
+/// Error returned by a failed state transition
+///
+/// The device is returned in its previous state.
+#[derive(Debug)]
+pub struct ModeChangeError<E, DEV> {
+    /// Error that occurred.
+    pub error: Error<E>,
+    /// Device in its previous state.
+    pub dev: DEV,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(self = "TypedTcs3472"),
+    async(
+        feature = "async",
+        self = "TypedTcs3472Async",
+        idents(Tcs3472(async = "Tcs3472Async"))
+    )
+)]
+/// Type-state front end of the TCS3472 driver.
+///
+/// The device state (`Off`, `Idle` or `Active`) is part of the type and
+/// the channels can only be read in the `Active` state, so reading while
+/// the device is asleep or the RGB converter is disabled fails at compile
+/// time. The dynamic API stays available through
+/// [`into_inner()`](#method.into_inner).
+///
+/// `TypedTcs3472` wraps `Tcs3472`. If the `async` feature is enabled,
+/// `TypedTcs3472Async` wraps `Tcs3472Async` in the same way.
+#[derive(Debug)]
+pub struct TypedTcs3472<I2C, STATE> {
+    dev: Tcs3472<I2C>,
+    _state: PhantomData<STATE>,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(self = "TypedTcs3472", idents(AsyncI2c(sync = "I2c"))),
+    async(
+        feature = "async",
+        self = "TypedTcs3472Async",
+        idents(Tcs3472(async = "Tcs3472Async"))
+    )
+)]
+impl<I2C, E> TypedTcs3472<I2C, Off>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Wrap a driver, powering the device off and disabling the RGB
+    /// converter.
+    ///
+    /// Other enable settings like the wait feature are preserved.
+    pub async fn new(mut dev: Tcs3472<I2C>) -> Result<Self, Error<E>> {
+        let enable = dev.shadow.enable() & !(BitFlags::POWER_ON | BitFlags::RGBC_EN);
+        dev.write_enable(enable).await?;
+        Ok(TypedTcs3472 {
+            dev,
+            _state: PhantomData,
+        })
+    }
+
+    /// Power the device on.
+    ///
+    /// The device needs a warm-up time of 2.4ms after being powered on
+    /// before the RGB converter can be enabled with
+    /// [`into_active()`](#method.into_active). Wait for it before the
+    /// transition, e.g. with `DelayNs::delay_us(2400)`.
+    pub async fn into_idle(mut self) -> Result<TypedTcs3472<I2C, Idle>, ModeChangeError<E, Self>> {
+        match self.dev.enable().await {
+            Ok(()) => Ok(TypedTcs3472 {
+                dev: self.dev,
+                _state: PhantomData,
+            }),
+            Err(error) => Err(ModeChangeError { error, dev: self }),
+        }
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(self = "TypedTcs3472", idents(AsyncI2c(sync = "I2c"))),
+    async(
+        feature = "async",
+        self = "TypedTcs3472Async",
+        idents(Tcs3472(async = "Tcs3472Async"))
+    )
+)]
+impl<I2C, E> TypedTcs3472<I2C, Idle>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Enable the RGB converter.
+    ///
+    /// If the device was just powered on with
+    /// [`into_idle()`](#method.into_idle), wait for the warm-up time of
+    /// 2.4ms first. The first measurement is available after the
+    /// integration time.
+    pub async fn into_active(
+        mut self,
+    ) -> Result<TypedTcs3472<I2C, Active>, ModeChangeError<E, Self>> {
+        match self.dev.enable_rgbc().await {
+            Ok(()) => Ok(TypedTcs3472 {
+                dev: self.dev,
+                _state: PhantomData,
+            }),
+            Err(error) => Err(ModeChangeError { error, dev: self }),
+        }
+    }
+
+    /// Power the device off.
+    pub async fn into_off(mut self) -> Result<TypedTcs3472<I2C, Off>, ModeChangeError<E, Self>> {
+        match self.dev.disable().await {
+            Ok(()) => Ok(TypedTcs3472 {
+                dev: self.dev,
+                _state: PhantomData,
+            }),
+            Err(error) => Err(ModeChangeError { error, dev: self }),
+        }
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(self = "TypedTcs3472", idents(AsyncI2c(sync = "I2c"))),
+    async(
+        feature = "async",
+        self = "TypedTcs3472Async",
+        idents(Tcs3472(async = "Tcs3472Async"))
+    )
+)]
+impl<I2C, E> TypedTcs3472<I2C, Active>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Disable the RGB converter.
+    pub async fn into_idle(mut self) -> Result<TypedTcs3472<I2C, Idle>, ModeChangeError<E, Self>> {
+        match self.dev.disable_rgbc().await {
+            Ok(()) => Ok(TypedTcs3472 {
+                dev: self.dev,
+                _state: PhantomData,
+            }),
+            Err(error) => Err(ModeChangeError { error, dev: self }),
+        }
+    }
+
+    /// Disable the RGB converter and power the device off in one write.
+    pub async fn into_off(mut self) -> Result<TypedTcs3472<I2C, Off>, ModeChangeError<E, Self>> {
+        let enable = self.dev.shadow.enable() & !(BitFlags::POWER_ON | BitFlags::RGBC_EN);
+        match self.dev.write_enable(enable).await {
+            Ok(()) => Ok(TypedTcs3472 {
+                dev: self.dev,
+                _state: PhantomData,
+            }),
+            Err(error) => Err(ModeChangeError { error, dev: self }),
+        }
+    }
+
+    /// Check whether the RGB converter status is valid.
+    ///
+    /// See [`Tcs3472::is_rgbc_status_valid()`].
+    pub async fn is_rgbc_status_valid(&mut self) -> Result<bool, Error<E>> {
+        self.dev.is_rgbc_status_valid().await
+    }
+
+    /// Read the clear (unfiltered) channel measurement data.
+    pub async fn read_clear_channel(&mut self) -> Result<u16, Error<E>> {
+        self.dev.read_clear_channel().await
+    }
+
+    /// Read the red channel measurement data.
+    pub async fn read_red_channel(&mut self) -> Result<u16, Error<E>> {
+        self.dev.read_red_channel().await
+    }
+
+    /// Read the green channel measurement data.
+    pub async fn read_green_channel(&mut self) -> Result<u16, Error<E>> {
+        self.dev.read_green_channel().await
+    }
+
+    /// Read the blue channel measurement data.
+    pub async fn read_blue_channel(&mut self) -> Result<u16, Error<E>> {
+        self.dev.read_blue_channel().await
+    }
+
+    /// Read the measurement data of all channels at once.
+    pub async fn read_all_channels(&mut self) -> Result<AllChannelMeasurement, Error<E>> {
+        self.dev.read_all_channels().await
+    }
+
+    /// Read the measurement data of all channels and classify their
+    /// saturation.
+    pub async fn read_all_channels_with_saturation(
+        &mut self,
+    ) -> Result<(AllChannelMeasurement, ChannelSaturation), Error<E>> {
+        self.dev.read_all_channels_with_saturation().await
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(self = "TypedTcs3472", idents(AsyncI2c(sync = "I2c"))),
+    async(
+        feature = "async",
+        self = "TypedTcs3472Async",
+        idents(Tcs3472(async = "Tcs3472Async"))
+    )
+)]
+impl<I2C, E, STATE> TypedTcs3472<I2C, STATE>
+where
+    I2C: AsyncI2c<Error = E>,
+{
+    /// Set the RGB converter gain.
+    pub async fn set_rgbc_gain(&mut self, gain: RgbCGain) -> Result<(), Error<E>> {
+        self.dev.set_rgbc_gain(gain).await
+    }
+
+    /// Set the number of integration cycles (1-256).
+    pub async fn set_integration_cycles(&mut self, cycles: u16) -> Result<(), Error<E>> {
+        self.dev.set_integration_cycles(cycles).await
+    }
+
+    /// Set the integration time in microseconds.
+    ///
+    /// See [`Tcs3472::set_integration_time_us()`].
+    pub async fn set_integration_time_us(&mut self, time_us: u32) -> Result<u32, Error<E>> {
+        self.dev.set_integration_time_us(time_us).await
+    }
+
+    /// Read the status register.
+    pub async fn read_status(&mut self) -> Result<Status, Error<E>> {
+        self.dev.read_status().await
+    }
+
+    /// Get the full-scale count of the RGBC channels for the configured
+    /// number of integration cycles.
+    pub fn full_scale_count(&self) -> u16 {
+        self.dev.full_scale_count()
+    }
+
+    /// Return the driver with the dynamic API.
+    pub fn into_inner(self) -> Tcs3472<I2C> {
+        self.dev
+    }
+
+    /// Destroy driver instance, return I²C bus instance.
+    pub fn destroy(self) -> I2C {
+        self.dev.destroy()
+    }
+}
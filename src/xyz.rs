@@ -0,0 +1,120 @@
+//! CIE 1931 XYZ and chromaticity conversion.
+
+use crate::AllChannelMeasurement;
+
+/// CIE 1931 XYZ tristimulus values
+///
+/// The values are relative and scale with the measurement counts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Xyz {
+    /// X tristimulus value.
+    pub x: f32,
+    /// Y tristimulus value (luminance).
+    pub y: f32,
+    /// Z tristimulus value.
+    pub z: f32,
+}
+
+/// CIE 1931 xy chromaticity coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Chromaticity {
+    /// x chromaticity coordinate.
+    pub x: f32,
+    /// y chromaticity coordinate.
+    pub y: f32,
+}
+
+impl Xyz {
+    /// Chromaticity coordinates.
+    ///
+    /// Returns `None` if the sum of the tristimulus values is not positive,
+    /// for example for a dark measurement.
+    pub fn chromaticity(&self) -> Option<Chromaticity> {
+        let sum = self.x + self.y + self.z;
+        if sum > 0.0 {
+            Some(Chromaticity {
+                x: self.x / sum,
+                y: self.y / sum,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Calibration matrix for the conversion of measurements into XYZ
+///
+/// The rows correspond to X, Y and Z and the columns to the red, green,
+/// blue and clear channels. A 3×3 matrix leaves the clear channel out.
+///
+/// For accurate results, a matrix should be derived for the concrete
+/// design from measurements with a reference meter, e.g. with
+/// [`CalibrationMatrix::solve()`]. Without one,
+/// [`CalibrationMatrix::tcs3414_approximation()`] gives rough values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalibrationMatrix {
+    coefficients: [[f32; 4]; 3],
+}
+
+impl CalibrationMatrix {
+    /// Matrix published by TAOS for the TCS3414CS.
+    ///
+    /// This is the matrix of the application note "Calculating Color
+    /// Temperature and Illuminance using the TAOS TCS3414CS Digital Color
+    /// Sensor" for the TCS3414CS, a predecessor with similar filters,
+    /// under the assumption of no glass or diffuser in front of the
+    /// sensor. It is only a rough approximation for the TCS3472.
+    pub const fn tcs3414_approximation() -> Self {
+        CalibrationMatrix::from_rgb([
+            [-0.14282, 1.54924, -0.95641],
+            [-0.32466, 1.57837, -0.73191],
+            [-0.68202, 0.77073, 0.56332],
+        ])
+    }
+
+    /// Create a 3×3 matrix using the red, green and blue channels.
+    pub const fn from_rgb(m: [[f32; 3]; 3]) -> Self {
+        CalibrationMatrix {
+            coefficients: [
+                [m[0][0], m[0][1], m[0][2], 0.0],
+                [m[1][0], m[1][1], m[1][2], 0.0],
+                [m[2][0], m[2][1], m[2][2], 0.0],
+            ],
+        }
+    }
+
+    /// Create a 3×4 matrix using the red, green, blue and clear channels.
+    pub const fn from_rgbc(coefficients: [[f32; 4]; 3]) -> Self {
+        CalibrationMatrix { coefficients }
+    }
+
+    /// Matrix coefficients.
+    ///
+    /// The rows correspond to X, Y and Z and the columns to the red, green,
+    /// blue and clear channels.
+    pub fn coefficients(&self) -> [[f32; 4]; 3] {
+        self.coefficients
+    }
+
+    /// Convert a measurement into XYZ tristimulus values.
+    pub fn to_xyz(&self, measurement: &AllChannelMeasurement) -> Xyz {
+        let channels = [
+            f32::from(measurement.red),
+            f32::from(measurement.green),
+            f32::from(measurement.blue),
+            f32::from(measurement.clear),
+        ];
+        let row = |i: usize| {
+            self.coefficients[i]
+                .iter()
+                .zip(channels.iter())
+                .map(|(c, v)| c * v)
+                .sum()
+        };
+        Xyz {
+            x: row(0),
+            y: row(1),
+            z: row(2),
+        }
+    }
+}
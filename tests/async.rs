@@ -0,0 +1,195 @@
+#![cfg(feature = "async")]
+mod common;
+use crate::common::{destroy, new, BitFlags, Register, DEV_ADDR};
+use embassy_futures::block_on;
+use embedded_hal::digital;
+use embedded_hal_mock::eh1::{
+    delay::{CheckedDelay, Transaction as DelayTrans},
+    digital::{Mock as PinMock, State as PinState, Transaction as PinTrans},
+    i2c::{Mock as I2cMock, Transaction as I2cTrans},
+    MockError,
+};
+use std::io::ErrorKind;
+use tcs3472::{Error, RgbCGain, SensorArrayAsync, Tcs3472Async, TypedTcs3472Async};
+
+#[test]
+fn can_enable_and_read_all_channels() {
+    let mut dev = Tcs3472Async::new(I2cMock::new(&[
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![BitFlags::CMD | Register::ENABLE, BitFlags::POWER_ON],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![BitFlags::CMD | Register::CONTROL, 2]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![BitFlags::CMD | BitFlags::CMD_AUTO_INC | Register::CDATA],
+            vec![0x23, 0x01, 0x67, 0x45, 0xAB, 0x89, 0xEF, 0xCD],
+        ),
+    ]));
+    let measurement = block_on(async {
+        dev.enable().await.unwrap();
+        dev.set_rgbc_gain(RgbCGain::_16x).await.unwrap();
+        dev.read_all_channels().await.unwrap()
+    });
+    assert_eq!(0x0123, measurement.clear);
+    assert_eq!(0xCDEF, measurement.blue);
+    dev.destroy().done();
+}
+
+#[test]
+fn blocking_driver_is_available_with_async_feature() {
+    let mut dev = new(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![BitFlags::CMD | Register::ID],
+        vec![0x44],
+    )]);
+    assert_eq!(0x44, dev.read_device_id().unwrap());
+    destroy(dev);
+}
+
+#[test]
+fn can_measure() {
+    let mut dev = Tcs3472Async::new(I2cMock::new(&[
+        I2cTrans::write_read(DEV_ADDR, vec![BitFlags::CMD | Register::ATIME], vec![0xFF]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![BitFlags::CMD | Register::ENABLE, BitFlags::POWER_ON],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                BitFlags::CMD | Register::ENABLE,
+                BitFlags::POWER_ON | BitFlags::RGBC_EN,
+            ],
+        ),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![BitFlags::CMD | Register::STATUS],
+            vec![BitFlags::RGBC_VALID],
+        ),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![BitFlags::CMD | BitFlags::CMD_AUTO_INC | Register::CDATA],
+            vec![0x23, 0x01, 0x67, 0x45, 0xAB, 0x89, 0xEF, 0xCD],
+        ),
+    ]));
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::async_delay_us(2400),
+        DelayTrans::async_delay_us(2400),
+    ]);
+    let m = block_on(dev.measure(&mut delay, false)).unwrap();
+    assert_eq!(0x0123, m.clear);
+    delay.done();
+    dev.destroy().done();
+}
+
+#[test]
+fn can_wait_for_rgbc_interrupt() {
+    let mut dev = Tcs3472Async::new(I2cMock::new(&[
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![BitFlags::CMD | BitFlags::CMD_AUTO_INC | Register::STATUS],
+            vec![
+                BitFlags::RGBC_INT | BitFlags::RGBC_VALID,
+                0x23,
+                0x01,
+                0x67,
+                0x45,
+                0xAB,
+                0x89,
+                0xEF,
+                0xCD,
+            ],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![BitFlags::CMD | BitFlags::CMD_SPECIAL_FN | BitFlags::RGBC_INT_CLEAR],
+        ),
+    ]));
+    let mut pin = PinMock::new(&[PinTrans::wait_for_state(PinState::Low)]);
+    let m = block_on(dev.wait_for_rgbc_interrupt(&mut pin)).unwrap();
+    assert_eq!(0x0123, m.clear);
+    assert_eq!(0xCDEF, m.blue);
+    pin.done();
+    dev.destroy().done();
+}
+
+#[test]
+fn wait_for_rgbc_interrupt_reports_pin_error() {
+    let mut dev = Tcs3472Async::new(I2cMock::new(&[]));
+    let mut pin = PinMock::new(&[
+        PinTrans::wait_for_state(PinState::Low).with_error(MockError::Io(ErrorKind::Other))
+    ]);
+    match block_on(dev.wait_for_rgbc_interrupt(&mut pin)) {
+        Err(Error::Pin(digital::ErrorKind::Other)) => (),
+        _ => panic!(),
+    }
+    pin.done();
+    dev.destroy().done();
+}
+
+#[test]
+fn typed_driver_can_read_when_active() {
+    let dev = Tcs3472Async::new(I2cMock::new(&[
+        I2cTrans::write(DEV_ADDR, vec![BitFlags::CMD | Register::ENABLE, 0]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![BitFlags::CMD | Register::ENABLE, BitFlags::POWER_ON],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                BitFlags::CMD | Register::ENABLE,
+                BitFlags::POWER_ON | BitFlags::RGBC_EN,
+            ],
+        ),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![BitFlags::CMD | BitFlags::CMD_AUTO_INC | Register::CDATA],
+            vec![0x23, 0x01, 0x67, 0x45, 0xAB, 0x89, 0xEF, 0xCD],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![BitFlags::CMD | Register::ENABLE, 0]),
+    ]));
+    let sensor = block_on(async {
+        let sensor = TypedTcs3472Async::new(dev).await.unwrap();
+        let sensor = sensor.into_idle().await.ok().unwrap();
+        let mut sensor = sensor.into_active().await.ok().unwrap();
+        let measurement = sensor.read_all_channels().await.unwrap();
+        assert_eq!(0x0123, measurement.clear);
+        sensor.into_off().await.ok().unwrap()
+    });
+    sensor.destroy().done();
+}
+
+#[test]
+fn can_read_all_sensors_of_array() {
+    let read = |clear| {
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![BitFlags::CMD | BitFlags::CMD_AUTO_INC | Register::CDATA],
+            vec![clear, 0, 0, 0, 0, 0, 0, 0],
+        )
+    };
+    let mut array = SensorArrayAsync::new([
+        Tcs3472Async::new(I2cMock::new(&[read(10)])),
+        Tcs3472Async::new(I2cMock::new(&[read(20)])),
+    ]);
+    let measurements = block_on(array.read_all_channels()).unwrap();
+    assert_eq!([10, 20], measurements.map(|m| m.clear));
+    for sensor in array.destroy() {
+        sensor.destroy().done();
+    }
+}
+
+#[test]
+fn can_set_integration_time() {
+    let mut dev = Tcs3472Async::new(I2cMock::new(&[I2cTrans::write(
+        DEV_ADDR,
+        vec![BitFlags::CMD | Register::ATIME, 0xF6],
+    )]));
+    assert_eq!(
+        24_000,
+        block_on(dev.set_integration_time_us(24_000)).unwrap()
+    );
+    dev.destroy().done();
+}
@@ -0,0 +1,146 @@
+mod common;
+use crate::common::{destroy, m, new, read_all_channels, write, BitFlags, Register};
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use tcs3472::{AutoRange, RgbCGain};
+
+fn set_gain(gain: u8) -> Vec<I2cTrans> {
+    vec![write(Register::CONTROL, gain)]
+}
+
+fn set_atime(atime: u8) -> Vec<I2cTrans> {
+    vec![write(Register::ATIME, atime)]
+}
+
+fn apply(gain: u8, atime: u8) -> Vec<I2cTrans> {
+    [set_gain(gain), set_atime(atime)].concat()
+}
+
+fn read(clear: u16) -> Vec<I2cTrans> {
+    vec![
+        common::read(Register::STATUS, BitFlags::RGBC_VALID),
+        read_all_channels(&m(1, 2, 3, clear)),
+    ]
+}
+
+#[test]
+fn applies_settings_and_discards_first_reading() {
+    let transactions = [apply(0, 0xC0), read(1000), read(20000)].concat();
+    let mut dev = new(&transactions);
+    let mut auto_range = AutoRange::new();
+    assert!(dev
+        .read_all_channels_auto_ranged(&mut auto_range)
+        .unwrap()
+        .is_none());
+    assert!(dev
+        .read_all_channels_auto_ranged(&mut auto_range)
+        .unwrap()
+        .is_none());
+    let result = dev
+        .read_all_channels_auto_ranged(&mut auto_range)
+        .unwrap()
+        .unwrap();
+    assert_eq!(20000, result.measurement.clear);
+    assert_eq!(RgbCGain::_1x, result.gain);
+    assert_eq!(64, result.integration_cycles);
+    destroy(dev);
+}
+
+#[test]
+fn returns_none_if_status_not_valid() {
+    let mut transactions = apply(0, 0xC0);
+    transactions.push(common::read(Register::STATUS, 0));
+    let mut dev = new(&transactions);
+    let mut auto_range = AutoRange::new();
+    assert!(dev
+        .read_all_channels_auto_ranged(&mut auto_range)
+        .unwrap()
+        .is_none());
+    assert!(dev
+        .read_all_channels_auto_ranged(&mut auto_range)
+        .unwrap()
+        .is_none());
+    destroy(dev);
+}
+
+#[test]
+fn increases_integration_time_then_gain_when_dark() {
+    let transactions = [
+        apply(0, 0xC0),
+        read(0),
+        read(100),
+        set_atime(0x00),
+        read(0),
+        read(100),
+        set_gain(1),
+    ]
+    .concat();
+    let mut dev = new(&transactions);
+    let mut auto_range = AutoRange::new();
+    for _ in 0..5 {
+        assert!(dev
+            .read_all_channels_auto_ranged(&mut auto_range)
+            .unwrap()
+            .is_none());
+    }
+    assert_eq!(RgbCGain::_4x, auto_range.gain());
+    assert_eq!(256, auto_range.integration_cycles());
+    destroy(dev);
+}
+
+#[test]
+fn decreases_gain_then_integration_time_when_bright() {
+    let transactions = [
+        apply(1, 0x00),
+        read(0),
+        read(65535),
+        set_gain(0),
+        read(0),
+        read(65535),
+        set_atime(0xC0),
+    ]
+    .concat();
+    let mut dev = new(&transactions);
+    let mut auto_range = AutoRange::new().with_initial_settings(RgbCGain::_4x, 256);
+    for _ in 0..5 {
+        assert!(dev
+            .read_all_channels_auto_ranged(&mut auto_range)
+            .unwrap()
+            .is_none());
+    }
+    assert_eq!(RgbCGain::_1x, auto_range.gain());
+    assert_eq!(64, auto_range.integration_cycles());
+    destroy(dev);
+}
+
+#[test]
+fn returns_out_of_window_measurement_at_limits() {
+    let transactions = [apply(0, 0xFF), read(0), read(1024)].concat();
+    let mut dev = new(&transactions);
+    let mut auto_range = AutoRange::new().with_integration_cycles_range(1, 1);
+    dev.read_all_channels_auto_ranged(&mut auto_range).unwrap();
+    dev.read_all_channels_auto_ranged(&mut auto_range).unwrap();
+    let result = dev
+        .read_all_channels_auto_ranged(&mut auto_range)
+        .unwrap()
+        .unwrap();
+    assert_eq!(1024, result.measurement.clear);
+    assert_eq!(1, result.integration_cycles);
+    destroy(dev);
+}
+
+#[test]
+fn can_normalize_measurement() {
+    let transactions = [apply(0, 0xC0), read(0), read(32000)].concat();
+    let mut dev = new(&transactions);
+    let mut auto_range = AutoRange::new();
+    dev.read_all_channels_auto_ranged(&mut auto_range).unwrap();
+    dev.read_all_channels_auto_ranged(&mut auto_range).unwrap();
+    let result = dev
+        .read_all_channels_auto_ranged(&mut auto_range)
+        .unwrap()
+        .unwrap();
+    let normalized = result.normalized();
+    assert_eq!(500.0, normalized.clear);
+    assert_eq!(1.0 / 64.0, normalized.red);
+    destroy(dev);
+}
@@ -0,0 +1,123 @@
+use tcs3472::{
+    AllChannelMeasurement, CalibrationChannels, CalibrationError, CalibrationMatrix,
+    CalibrationSample,
+};
+
+const MEASUREMENTS: [AllChannelMeasurement; 6] = [
+    AllChannelMeasurement {
+        red: 3000,
+        green: 800,
+        blue: 500,
+        clear: 4600,
+    },
+    AllChannelMeasurement {
+        red: 700,
+        green: 2500,
+        blue: 900,
+        clear: 4300,
+    },
+    AllChannelMeasurement {
+        red: 400,
+        green: 900,
+        blue: 2800,
+        clear: 4400,
+    },
+    AllChannelMeasurement {
+        red: 2000,
+        green: 2100,
+        blue: 1900,
+        clear: 6500,
+    },
+    AllChannelMeasurement {
+        red: 1500,
+        green: 2600,
+        blue: 600,
+        clear: 4900,
+    },
+    AllChannelMeasurement {
+        red: 250,
+        green: 300,
+        blue: 280,
+        clear: 1000,
+    },
+];
+
+fn samples(matrix: &CalibrationMatrix) -> [CalibrationSample; 6] {
+    MEASUREMENTS.map(|measurement| CalibrationSample {
+        measurement,
+        reference: matrix.to_xyz(&measurement),
+    })
+}
+
+fn assert_matrix_close(expected: &CalibrationMatrix, actual: &CalibrationMatrix) {
+    for (e, a) in expected
+        .coefficients()
+        .iter()
+        .flatten()
+        .zip(actual.coefficients().iter().flatten())
+    {
+        assert!((e - a).abs() < 1e-3, "expected {e}, got {a}");
+    }
+}
+
+#[test]
+fn can_recover_rgb_matrix() {
+    let expected = CalibrationMatrix::tcs3414_approximation();
+    let samples = samples(&expected);
+    let matrix = CalibrationMatrix::solve(&samples, CalibrationChannels::Rgb).unwrap();
+    assert_matrix_close(&expected, &matrix);
+    let mut residuals = [1.0; 6];
+    matrix.residuals(&samples, &mut residuals);
+    for residual in residuals {
+        assert!(residual < 0.1);
+    }
+}
+
+#[test]
+fn can_recover_rgbc_matrix() {
+    let expected = CalibrationMatrix::from_rgbc([
+        [0.5, 0.2, 0.1, 0.05],
+        [0.2, 0.7, 0.05, -0.02],
+        [0.02, 0.1, 0.9, 0.01],
+    ]);
+    let samples = samples(&expected);
+    let matrix = CalibrationMatrix::solve(&samples, CalibrationChannels::Rgbc).unwrap();
+    assert_matrix_close(&expected, &matrix);
+}
+
+#[test]
+fn reports_residual_of_inconsistent_sample() {
+    let matrix = CalibrationMatrix::from_rgb([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+    let mut samples = samples(&matrix);
+    samples[3].reference.x += 300.0;
+    samples[3].reference.y += 400.0;
+    let fitted = CalibrationMatrix::solve(&samples, CalibrationChannels::Rgb).unwrap();
+    let mut residuals = [0.0; 6];
+    fitted.residuals(&samples, &mut residuals);
+    let worst = residuals
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .unwrap();
+    assert_eq!(3, worst.0);
+    assert!(*worst.1 > 100.0);
+    assert_eq!(500.0, matrix.residual(&samples[3]));
+}
+
+#[test]
+fn needs_enough_samples() {
+    let samples = samples(&CalibrationMatrix::tcs3414_approximation());
+    assert_eq!(
+        Err(CalibrationError::NotEnoughSamples),
+        CalibrationMatrix::solve(&samples[..3], CalibrationChannels::Rgbc)
+    );
+}
+
+#[test]
+fn detects_singular_samples() {
+    let samples = [samples(&CalibrationMatrix::tcs3414_approximation())[0]; 4];
+    assert_eq!(
+        Err(CalibrationError::Singular),
+        CalibrationMatrix::solve(&samples, CalibrationChannels::Rgb)
+    );
+}
@@ -0,0 +1,129 @@
+mod common;
+use crate::common::m;
+use tcs3472::{AllChannelMeasurement, Classification, ClassifierError, ColorClassifier};
+
+fn rgb(red: u16, green: u16, blue: u16) -> AllChannelMeasurement {
+    m(red, green, blue, red + green + blue)
+}
+
+fn trained() -> ColorClassifier<&'static str, 4> {
+    let mut classifier = ColorClassifier::new();
+    classifier.add_sample("red", &rgb(600, 200, 200)).unwrap();
+    classifier.add_sample("green", &rgb(200, 600, 200)).unwrap();
+    classifier.add_sample("blue", &rgb(200, 200, 600)).unwrap();
+    classifier
+}
+
+fn label(classification: Classification<&'static str>) -> Option<&'static str> {
+    match classification {
+        Classification::Known { label, .. } => Some(label),
+        Classification::Unknown => None,
+    }
+}
+
+#[test]
+fn empty_classifier_returns_unknown() {
+    let classifier = ColorClassifier::<u8, 4>::new();
+    assert!(classifier.is_empty());
+    assert_eq!(Classification::Unknown, classifier.classify(&rgb(1, 2, 3)));
+}
+
+#[test]
+fn can_classify() {
+    let classifier = trained();
+    assert_eq!(3, classifier.len());
+    assert_eq!(Some("red"), label(classifier.classify(&rgb(500, 250, 250))));
+    assert_eq!(
+        Some("green"),
+        label(classifier.classify(&rgb(250, 500, 250)))
+    );
+    assert_eq!(
+        Some("blue"),
+        label(classifier.classify(&rgb(250, 250, 500)))
+    );
+}
+
+#[test]
+fn classification_is_intensity_invariant() {
+    let classifier = trained();
+    for scale in [1, 10, 100] {
+        assert_eq!(
+            Classification::Known {
+                label: "green",
+                distance: 0.0,
+                confidence: 1.0
+            },
+            classifier.classify(&rgb(20 * scale, 60 * scale, 20 * scale))
+        );
+    }
+}
+
+#[test]
+fn can_calculate_confidence() {
+    let classifier = trained();
+    match classifier.classify(&rgb(400, 400, 200)) {
+        Classification::Known {
+            distance,
+            confidence,
+            ..
+        } => {
+            assert!((distance - 0.282_843).abs() < 1e-5);
+            assert_eq!(0.0, confidence);
+        }
+        Classification::Unknown => panic!("unexpected unknown"),
+    }
+}
+
+#[test]
+fn single_class_has_full_confidence() {
+    let mut classifier = ColorClassifier::<u8, 2>::new();
+    classifier.add_sample(1, &rgb(600, 200, 200)).unwrap();
+    classifier.add_sample(1, &rgb(500, 300, 200)).unwrap();
+    match classifier.classify(&rgb(200, 200, 600)) {
+        Classification::Known {
+            label, confidence, ..
+        } => {
+            assert_eq!(1, label);
+            assert_eq!(1.0, confidence);
+        }
+        Classification::Unknown => panic!("unexpected unknown"),
+    }
+}
+
+#[test]
+fn rejects_distant_colors() {
+    let classifier = trained().with_rejection_threshold(0.05);
+    assert_eq!(Some("red"), label(classifier.classify(&rgb(580, 210, 210))));
+    assert_eq!(
+        Classification::Unknown,
+        classifier.classify(&rgb(400, 400, 200))
+    );
+}
+
+#[test]
+fn dark_measurement_is_unknown() {
+    assert_eq!(Classification::Unknown, trained().classify(&rgb(0, 0, 0)));
+}
+
+#[test]
+fn cannot_add_dark_sample() {
+    let mut classifier = ColorClassifier::<u8, 2>::new();
+    assert_eq!(
+        Err(ClassifierError::Dark),
+        classifier.add_sample(1, &rgb(0, 0, 0))
+    );
+    assert!(classifier.is_empty());
+}
+
+#[test]
+fn cannot_add_sample_when_full() {
+    let mut classifier = trained();
+    classifier.add_sample("white", &rgb(300, 300, 300)).unwrap();
+    assert_eq!(
+        Err(ClassifierError::Full),
+        classifier.add_sample("black", &rgb(1, 1, 1))
+    );
+    classifier.clear();
+    assert!(classifier.is_empty());
+    classifier.add_sample("black", &rgb(1, 1, 1)).unwrap();
+}
@@ -1,7 +1,9 @@
 use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTrans};
-use tcs3472::Tcs3472;
+use tcs3472::{AllChannelMeasurement, Config, DeviceVariant, Tcs3472};
 
 pub const DEV_ADDR: u8 = 0x29;
+#[allow(unused)]
+pub const DEV_ADDR_ALT: u8 = 0x39;
 
 pub struct Register;
 
@@ -29,18 +31,128 @@ pub struct BitFlags;
 impl BitFlags {
     pub const CMD: u8 = 0b1000_0000;
     pub const CMD_AUTO_INC: u8 = 0b0010_0000;
+    pub const CMD_SPECIAL_FN: u8 = 0b0110_0000;
+    pub const RGBC_INT_CLEAR: u8 = 0b0000_0110;
     pub const POWER_ON: u8 = 0b0000_0001; // PON
     pub const RGBC_EN: u8 = 0b0000_0010; // AEN
     pub const WAIT_EN: u8 = 0b0000_1000; // WEN
     pub const RGBC_INT_EN: u8 = 0b0001_0000; // AIEN
     pub const RGBC_VALID: u8 = 0b0000_0001; // AVALID
+    pub const RGBC_INT: u8 = 0b0001_0000; // AINT
     pub const WLONG: u8 = 0b0000_0010;
 }
 
+#[allow(unused)]
 pub fn new(transactions: &[I2cTrans]) -> Tcs3472<I2cMock> {
     Tcs3472::new(I2cMock::new(transactions))
 }
 
+#[allow(unused)]
+pub fn new_with_variant(transactions: &[I2cTrans], variant: DeviceVariant) -> Tcs3472<I2cMock> {
+    Tcs3472::new_with_variant(I2cMock::new(transactions), variant)
+}
+
+#[allow(unused)]
 pub fn destroy(sensor: Tcs3472<I2cMock>) {
     sensor.destroy().done();
 }
+
+#[allow(unused)]
+pub fn write(register: u8, value: u8) -> I2cTrans {
+    I2cTrans::write(DEV_ADDR, vec![BitFlags::CMD | register, value])
+}
+
+#[allow(unused)]
+pub fn write_burst(first_register: u8, values: &[u8]) -> I2cTrans {
+    let mut data = vec![BitFlags::CMD | BitFlags::CMD_AUTO_INC | first_register];
+    data.extend_from_slice(values);
+    I2cTrans::write(DEV_ADDR, data)
+}
+
+#[allow(unused)]
+pub fn read(register: u8, value: u8) -> I2cTrans {
+    I2cTrans::write_read(DEV_ADDR, vec![BitFlags::CMD | register], vec![value])
+}
+
+#[allow(unused)]
+pub fn read_burst(first_register: u8, data: &[u8]) -> I2cTrans {
+    I2cTrans::write_read(
+        DEV_ADDR,
+        vec![BitFlags::CMD | BitFlags::CMD_AUTO_INC | first_register],
+        data.to_vec(),
+    )
+}
+
+#[allow(unused)]
+pub fn read_all_channels(measurement: &AllChannelMeasurement) -> I2cTrans {
+    let channels = [
+        measurement.clear,
+        measurement.red,
+        measurement.green,
+        measurement.blue,
+    ];
+    let data: Vec<u8> = channels.iter().flat_map(|c| c.to_le_bytes()).collect();
+    read_burst(Register::CDATA, &data)
+}
+
+/// Transactions applying a configuration with nothing known in the
+/// shadow copy of the driver.
+#[allow(unused)]
+pub fn apply_config(config: &Config) -> Vec<I2cTrans> {
+    let [ailtl, ailth] = config.rgbc_interrupt_low_threshold().to_le_bytes();
+    let [aihtl, aihth] = config.rgbc_interrupt_high_threshold().to_le_bytes();
+    let flags = config.enable_flags();
+    let mut enable = 0;
+    for (set, bit) in [
+        (flags.power_on, BitFlags::POWER_ON),
+        (flags.rgbc, BitFlags::RGBC_EN),
+        (flags.wait, BitFlags::WAIT_EN),
+        (flags.rgbc_interrupts, BitFlags::RGBC_INT_EN),
+    ] {
+        if set {
+            enable |= bit;
+        }
+    }
+    let wlong = if config.wait_long() {
+        BitFlags::WLONG
+    } else {
+        0
+    };
+    vec![
+        write(Register::ATIME, (256 - config.integration_cycles()) as u8),
+        write_burst(
+            Register::WTIME,
+            &[
+                (256 - config.wait_cycles()) as u8,
+                ailtl,
+                ailth,
+                aihtl,
+                aihth,
+            ],
+        ),
+        write_burst(
+            Register::APERS,
+            &[config.rgbc_interrupt_persistence() as u8, wlong],
+        ),
+        write(Register::CONTROL, config.gain() as u8),
+        write(Register::ENABLE, enable),
+    ]
+}
+
+#[allow(unused)]
+pub fn m(red: u16, green: u16, blue: u16, clear: u16) -> AllChannelMeasurement {
+    AllChannelMeasurement {
+        red,
+        green,
+        blue,
+        clear,
+    }
+}
+
+#[allow(unused)]
+pub fn assert_close(expected: f32, actual: f32, tolerance: f32) {
+    assert!(
+        (expected - actual).abs() < tolerance,
+        "expected {expected}, got {actual}"
+    );
+}
@@ -0,0 +1,151 @@
+mod common;
+use crate::common::{destroy, new, read_burst, write, write_burst, BitFlags, Register};
+use tcs3472::{Config, ConfigError, EnableFlags, Error, RgbCGain, RgbCInterruptPersistence};
+
+fn config() -> Config {
+    Config::builder()
+        .enable_flags(EnableFlags {
+            power_on: true,
+            rgbc: true,
+            wait: true,
+            rgbc_interrupts: false,
+        })
+        .gain(RgbCGain::_16x)
+        .integration_cycles(10)
+        .wait_cycles(85)
+        .wait_long(true)
+        .rgbc_interrupt_low_threshold(0x0400)
+        .rgbc_interrupt_high_threshold(0xF000)
+        .rgbc_interrupt_persistence(RgbCInterruptPersistence::_5)
+        .build()
+        .unwrap()
+}
+
+const ENABLE: u8 = BitFlags::POWER_ON | BitFlags::RGBC_EN | BitFlags::WAIT_EN;
+
+#[test]
+fn default_is_power_on_state() {
+    let config = Config::builder().build().unwrap();
+    assert_eq!(Config::default(), config);
+    assert_eq!(1, config.integration_cycles());
+    assert_eq!(1, config.wait_cycles());
+    assert_eq!(RgbCGain::_1x, config.gain());
+    assert!(!config.enable_flags().power_on);
+}
+
+#[test]
+fn builder_sets_values() {
+    let config = config();
+    assert_eq!(RgbCGain::_16x, config.gain());
+    assert_eq!(10, config.integration_cycles());
+    assert_eq!(85, config.wait_cycles());
+    assert!(config.wait_long());
+    assert_eq!(0x0400, config.rgbc_interrupt_low_threshold());
+    assert_eq!(0xF000, config.rgbc_interrupt_high_threshold());
+    assert_eq!(
+        RgbCInterruptPersistence::_5,
+        config.rgbc_interrupt_persistence()
+    );
+}
+
+macro_rules! invalid_config_test {
+    ($name:ident, $method:ident, $value:expr, $expected:ident) => {
+        #[test]
+        fn $name() {
+            assert_eq!(
+                Err(ConfigError::$expected),
+                Config::builder().$method($value).build()
+            );
+        }
+    };
+}
+
+invalid_config_test!(cannot_build_ic_0, integration_cycles, 0, IntegrationCycles);
+invalid_config_test!(
+    cannot_build_ic_257,
+    integration_cycles,
+    257,
+    IntegrationCycles
+);
+invalid_config_test!(cannot_build_wc_0, wait_cycles, 0, WaitCycles);
+invalid_config_test!(cannot_build_wc_257, wait_cycles, 257, WaitCycles);
+
+#[test]
+fn config_error_converts_to_invalid_input_data() {
+    match Error::<()>::from(ConfigError::WaitCycles) {
+        Error::InvalidInputData => (),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn can_modify_existing_config() {
+    let config = tcs3472::ConfigBuilder::from(config())
+        .gain(RgbCGain::_60x)
+        .build()
+        .unwrap();
+    assert_eq!(RgbCGain::_60x, config.gain());
+    assert_eq!(10, config.integration_cycles());
+}
+
+#[test]
+fn can_apply_config() {
+    let mut dev = new(&[
+        write(Register::ATIME, 0xF6),
+        write_burst(Register::WTIME, &[0xAB, 0x00, 0x04, 0x00, 0xF0]),
+        write_burst(Register::APERS, &[4, BitFlags::WLONG]),
+        write(Register::CONTROL, 2),
+        write(Register::ENABLE, ENABLE),
+    ]);
+    dev.apply_config(&config()).unwrap();
+    assert_eq!(10240, dev.full_scale_count());
+    destroy(dev);
+}
+
+#[test]
+fn can_read_config() {
+    let mut dev = new(&[
+        read_burst(
+            Register::ENABLE,
+            &[
+                ENABLE,
+                0xF6,
+                0,
+                0xAB,
+                0x00,
+                0x04,
+                0x00,
+                0xF0,
+                0,
+                0,
+                0,
+                0,
+                4,
+                BitFlags::WLONG,
+                0,
+                2,
+            ],
+        ),
+        // enable state is kept
+        write(Register::ENABLE, ENABLE & !BitFlags::WAIT_EN),
+    ]);
+    assert_eq!(config(), dev.read_config().unwrap());
+    assert_eq!(10240, dev.full_scale_count());
+    dev.disable_wait().unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn reserved_bits_are_ignored_when_reading_config() {
+    let mut data = [0; 16];
+    data[usize::from(Register::CONTROL)] = 0x06;
+    data[usize::from(Register::APERS)] = 0x10;
+    let mut dev = new(&[read_burst(Register::ENABLE, &data)]);
+    let config = dev.read_config().unwrap();
+    assert_eq!(RgbCGain::_16x, config.gain());
+    assert_eq!(
+        RgbCInterruptPersistence::Every,
+        config.rgbc_interrupt_persistence()
+    );
+    destroy(dev);
+}
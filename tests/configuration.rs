@@ -1,7 +1,7 @@
 mod common;
 use crate::common::{destroy, new, BitFlags, Register, DEV_ADDR};
 use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
-use tcs3472::{Error, RgbCGain, RgbCInterruptPersistence};
+use tcs3472::{EnableFlags, Error, RgbCGain, RgbCInterruptPersistence, RgbCInterruptTrigger};
 
 #[test]
 fn can_create_and_destroy() {
@@ -95,6 +95,10 @@ macro_rules! set_invalid_param_test {
 
 set_invalid_param_test!(cannot_set_ic_0, set_integration_cycles, 0);
 set_invalid_param_test!(cannot_set_ic_greater_256, set_integration_cycles, 257);
+set_invalid_param_test!(cannot_set_it_too_short, set_integration_time_us, 1199);
+set_invalid_param_test!(cannot_set_it_too_long, set_integration_time_us, 615_600);
+set_invalid_param_test!(cannot_set_wt_too_short, set_wait_time_us, 1199);
+set_invalid_param_test!(cannot_set_wt_too_long, set_wait_time_us, 7_387_200);
 
 macro_rules! set_single_param_test {
     ($name:ident, $method:ident, $value:expr, $register:ident, $expected:expr) => {
@@ -229,3 +233,282 @@ set_int_pers_test!(can_set_rgbc_int_pers_45, _45, 12);
 set_int_pers_test!(can_set_rgbc_int_pers_50, _50, 13);
 set_int_pers_test!(can_set_rgbc_int_pers_55, _55, 14);
 set_int_pers_test!(can_set_rgbc_int_pers_60, _60, 15);
+
+macro_rules! read_single_param_test {
+    ($name:ident, $method:ident, $register:ident, $value:expr, $expected:expr) => {
+        #[test]
+        fn $name() {
+            let mut dev = new(&[I2cTrans::write_read(
+                DEV_ADDR,
+                vec![BitFlags::CMD | Register::$register],
+                vec![$value],
+            )]);
+            assert_eq!($expected, dev.$method().unwrap());
+            destroy(dev);
+        }
+    };
+}
+
+read_single_param_test!(can_read_ic_1, read_integration_cycles, ATIME, 0xFF, 1);
+read_single_param_test!(can_read_ic_10, read_integration_cycles, ATIME, 0xF6, 10);
+read_single_param_test!(can_read_ic_256, read_integration_cycles, ATIME, 0x00, 256);
+
+read_single_param_test!(
+    can_read_it_24ms,
+    read_integration_time_us,
+    ATIME,
+    0xF6,
+    24_000
+);
+
+read_single_param_test!(can_read_wc_1, read_wait_cycles, WTIME, 0xFF, 1);
+read_single_param_test!(can_read_wc_85, read_wait_cycles, WTIME, 0xAB, 85);
+read_single_param_test!(can_read_wc_256, read_wait_cycles, WTIME, 0x00, 256);
+
+read_single_param_test!(
+    can_read_wait_long_enabled,
+    is_wait_long_enabled,
+    CONFIG,
+    BitFlags::WLONG,
+    true
+);
+read_single_param_test!(
+    can_read_wait_long_disabled,
+    is_wait_long_enabled,
+    CONFIG,
+    0,
+    false
+);
+
+read_single_param_test!(
+    can_read_rgbc_gain_1x,
+    read_rgbc_gain,
+    CONTROL,
+    0,
+    RgbCGain::_1x
+);
+read_single_param_test!(
+    can_read_rgbc_gain_4x,
+    read_rgbc_gain,
+    CONTROL,
+    1,
+    RgbCGain::_4x
+);
+read_single_param_test!(
+    can_read_rgbc_gain_16x,
+    read_rgbc_gain,
+    CONTROL,
+    2,
+    RgbCGain::_16x
+);
+read_single_param_test!(
+    can_read_rgbc_gain_60x,
+    read_rgbc_gain,
+    CONTROL,
+    3,
+    RgbCGain::_60x
+);
+
+read_single_param_test!(
+    can_read_rgbc_int_pers_every,
+    read_rgbc_interrupt_persistence,
+    APERS,
+    0,
+    RgbCInterruptPersistence::Every
+);
+read_single_param_test!(
+    can_read_rgbc_int_pers_5,
+    read_rgbc_interrupt_persistence,
+    APERS,
+    4,
+    RgbCInterruptPersistence::_5
+);
+read_single_param_test!(
+    can_read_rgbc_int_pers_60,
+    read_rgbc_interrupt_persistence,
+    APERS,
+    15,
+    RgbCInterruptPersistence::_60
+);
+
+read_single_param_test!(
+    can_read_rgbc_gain_with_reserved_bits,
+    read_rgbc_gain,
+    CONTROL,
+    0xF6,
+    RgbCGain::_16x
+);
+read_single_param_test!(
+    can_read_rgbc_int_pers_with_reserved_bits,
+    read_rgbc_interrupt_persistence,
+    APERS,
+    0x1F,
+    RgbCInterruptPersistence::_60
+);
+
+macro_rules! read_threshold_test {
+    ($name:ident, $method:ident, $register:ident, $value0:expr, $value1:expr, $expected:expr) => {
+        #[test]
+        fn $name() {
+            let mut dev = new(&[I2cTrans::write_read(
+                DEV_ADDR,
+                vec![BitFlags::CMD | BitFlags::CMD_AUTO_INC | Register::$register],
+                vec![$value0, $value1],
+            )]);
+            assert_eq!($expected, dev.$method().unwrap());
+            destroy(dev);
+        }
+    };
+}
+
+read_threshold_test!(
+    can_read_rgbc_int_low_th,
+    read_rgbc_interrupt_low_threshold,
+    AILTL,
+    0xCD,
+    0xAB,
+    0xABCD
+);
+read_threshold_test!(
+    can_read_rgbc_int_high_th,
+    read_rgbc_interrupt_high_threshold,
+    AIHTL,
+    0xFF,
+    0xFF,
+    65535
+);
+
+#[test]
+fn can_read_enable_flags_and_keep_them() {
+    let mut dev = new(&[
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![BitFlags::CMD | Register::ENABLE],
+            vec![BitFlags::POWER_ON | BitFlags::WAIT_EN],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                BitFlags::CMD | Register::ENABLE,
+                BitFlags::POWER_ON | BitFlags::WAIT_EN | BitFlags::RGBC_EN,
+            ],
+        ),
+    ]);
+    let flags = dev.read_enable_flags().unwrap();
+    assert_eq!(
+        EnableFlags {
+            power_on: true,
+            rgbc: false,
+            wait: true,
+            rgbc_interrupts: false,
+        },
+        flags
+    );
+    dev.enable_rgbc().unwrap();
+    destroy(dev);
+}
+
+fn clear_and_enable_rgbc_interrupt() -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![BitFlags::CMD | BitFlags::CMD_SPECIAL_FN | BitFlags::RGBC_INT_CLEAR],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![BitFlags::CMD | Register::ENABLE, BitFlags::RGBC_INT_EN],
+        ),
+    ]
+}
+
+#[test]
+fn can_configure_rgbc_interrupt_every_cycle() {
+    let mut transactions = vec![I2cTrans::write(
+        DEV_ADDR,
+        vec![BitFlags::CMD | Register::APERS, 0],
+    )];
+    transactions.extend(clear_and_enable_rgbc_interrupt());
+    let mut dev = new(&transactions);
+    dev.configure_rgbc_interrupt(RgbCInterruptTrigger::EveryCycle)
+        .unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_configure_rgbc_interrupt_thresholds() {
+    let mut transactions = vec![
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                BitFlags::CMD | BitFlags::CMD_AUTO_INC | Register::AILTL,
+                0x00,
+                0x04,
+            ],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                BitFlags::CMD | BitFlags::CMD_AUTO_INC | Register::AIHTL,
+                0x00,
+                0xF0,
+            ],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![BitFlags::CMD | Register::APERS, 4]),
+    ];
+    transactions.extend(clear_and_enable_rgbc_interrupt());
+    let mut dev = new(&transactions);
+    dev.configure_rgbc_interrupt(RgbCInterruptTrigger::Thresholds {
+        low: 1024,
+        high: 61440,
+        persistence: RgbCInterruptPersistence::_5,
+    })
+    .unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_set_integration_time_rounded() {
+    let mut dev = new(&[I2cTrans::write(
+        DEV_ADDR,
+        vec![BitFlags::CMD | Register::ATIME, 0xF6],
+    )]);
+    assert_eq!(24_000, dev.set_integration_time_us(25_000).unwrap());
+    destroy(dev);
+}
+
+#[test]
+fn can_set_wait_time() {
+    let mut dev = new(&[
+        I2cTrans::write(DEV_ADDR, vec![BitFlags::CMD | Register::WTIME, 0xD6]),
+        I2cTrans::write(DEV_ADDR, vec![BitFlags::CMD | Register::CONFIG, 0]),
+    ]);
+    assert_eq!(100_800, dev.set_wait_time_us(100_000).unwrap());
+    destroy(dev);
+}
+
+#[test]
+fn can_set_long_wait_time() {
+    let mut dev = new(&[
+        I2cTrans::write(DEV_ADDR, vec![BitFlags::CMD | Register::WTIME, 0xDD]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![BitFlags::CMD | Register::CONFIG, BitFlags::WLONG],
+        ),
+    ]);
+    assert_eq!(1_008_000, dev.set_wait_time_us(1_000_000).unwrap());
+    destroy(dev);
+}
+
+#[test]
+fn can_read_wait_time() {
+    let mut dev = new(&[
+        I2cTrans::write_read(DEV_ADDR, vec![BitFlags::CMD | Register::WTIME], vec![0xDD]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![BitFlags::CMD | Register::CONFIG],
+            vec![BitFlags::WLONG],
+        ),
+    ]);
+    assert_eq!(1_008_000, dev.read_wait_time_us().unwrap());
+    destroy(dev);
+}
@@ -0,0 +1,47 @@
+mod common;
+use crate::common::m;
+use tcs3472::IrCompensated;
+
+#[test]
+fn can_estimate_ir() {
+    assert_eq!(150, m(500, 600, 400, 1200).ir_estimate());
+}
+
+#[test]
+fn negative_estimate_is_clamped() {
+    assert_eq!(0, m(300, 400, 200, 1200).ir_estimate());
+}
+
+#[test]
+fn estimate_does_not_overflow() {
+    assert_eq!(65535, m(65535, 65535, 65535, 0).ir_estimate());
+    assert_eq!(27232, m(40000, 40000, 40000, 65535).ir_estimate());
+}
+
+#[test]
+fn can_compensate() {
+    let compensated = m(500, 600, 400, 1200).ir_compensated();
+    assert_eq!(
+        IrCompensated {
+            ir: 150,
+            measurement: m(350, 450, 250, 1050)
+        },
+        compensated
+    );
+}
+
+#[test]
+fn compensated_channels_are_clamped() {
+    let compensated = m(2000, 100, 100, 1000).ir_compensated();
+    assert_eq!(600, compensated.ir);
+    assert_eq!(m(1400, 0, 0, 400), compensated.measurement);
+}
+
+#[test]
+fn without_ir_measurement_is_unchanged() {
+    let measurement = m(300, 400, 200, 1200);
+    assert_eq!(
+        IrCompensated { ir: 0, measurement },
+        IrCompensated::from(measurement)
+    );
+}
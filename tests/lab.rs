@@ -0,0 +1,167 @@
+mod common;
+use crate::common::assert_close;
+use tcs3472::{AllChannelMeasurement, CalibrationMatrix, Illuminant, Lab, LabConverter, Xyz};
+
+fn lab(l: f32, a: f32, b: f32) -> Lab {
+    Lab { l, a, b }
+}
+
+const SRGB_RED: Xyz = Xyz {
+    x: 0.412_456,
+    y: 0.212_673,
+    z: 0.019_334,
+};
+
+#[test]
+fn white_point_is_neutral() {
+    for illuminant in [Illuminant::D65, Illuminant::D50, Illuminant::A] {
+        let white = illuminant.white_point();
+        let lab = white.to_lab(&white);
+        assert_close(100.0, lab.l, 1e-4);
+        assert_close(0.0, lab.a, 1e-4);
+        assert_close(0.0, lab.b, 1e-4);
+        let luv = white.to_luv(&white);
+        assert_close(100.0, luv.l, 1e-4);
+        assert_close(0.0, luv.u, 1e-4);
+        assert_close(0.0, luv.v, 1e-4);
+    }
+}
+
+#[test]
+fn can_convert_to_lab() {
+    let lab = SRGB_RED.to_lab(&Illuminant::D65.white_point());
+    assert_close(53.24, lab.l, 0.01);
+    assert_close(80.09, lab.a, 0.02);
+    assert_close(67.20, lab.b, 0.02);
+}
+
+#[test]
+fn can_convert_to_luv() {
+    let luv = SRGB_RED.to_luv(&Illuminant::D65.white_point());
+    assert_close(53.24, luv.l, 0.01);
+    assert_close(175.01, luv.u, 0.05);
+    assert_close(37.76, luv.v, 0.05);
+}
+
+#[test]
+fn black_is_zero() {
+    let black = Xyz {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+    let white = Illuminant::D50.white_point();
+    assert_eq!(lab(0.0, 0.0, 0.0), black.to_lab(&white));
+    let luv = black.to_luv(&white);
+    assert_eq!((0.0, 0.0, 0.0), (luv.l, luv.u, luv.v));
+}
+
+#[test]
+fn can_calculate_delta_e76() {
+    assert_close(
+        5.0,
+        lab(50.0, 3.0, 4.0).delta_e76(&lab(50.0, 6.0, 8.0)),
+        1e-5,
+    );
+    assert_close(
+        3.0,
+        lab(50.0, 0.0, 0.0).delta_e76(&lab(53.0, 0.0, 0.0)),
+        1e-5,
+    );
+}
+
+#[test]
+fn can_calculate_delta_e94() {
+    // chroma difference only
+    let reference = lab(50.0, 3.0, 4.0);
+    assert_close(4.0816, lab(50.0, 6.0, 8.0).delta_e94(&reference), 1e-4);
+    // hue difference only
+    let reference = lab(50.0, 5.0, 0.0);
+    assert_close(6.5777, lab(50.0, 0.0, 5.0).delta_e94(&reference), 1e-4);
+    // lightness difference only
+    assert_close(2.0, lab(52.0, 5.0, 0.0).delta_e94(&reference), 1e-5);
+}
+
+macro_rules! delta_e2000_test {
+    ($name:ident, ($l1:expr, $a1:expr, $b1:expr), ($l2:expr, $a2:expr, $b2:expr), $expected:expr) => {
+        #[test]
+        fn $name() {
+            let lab1 = lab($l1, $a1, $b1);
+            let lab2 = lab($l2, $a2, $b2);
+            assert_close($expected, lab1.delta_e2000(&lab2), 1e-4);
+            assert_close($expected, lab2.delta_e2000(&lab1), 1e-4);
+        }
+    };
+}
+
+// Reference data from Sharma, Wu and Dalal (2005).
+delta_e2000_test!(
+    de2000_1,
+    (50.0, 2.6772, -79.7751),
+    (50.0, 0.0, -82.7485),
+    2.0425
+);
+delta_e2000_test!(
+    de2000_2,
+    (50.0, 3.1571, -77.2803),
+    (50.0, 0.0, -82.7485),
+    2.8615
+);
+delta_e2000_test!(
+    de2000_3,
+    (50.0, 2.8361, -74.0200),
+    (50.0, 0.0, -82.7485),
+    3.4412
+);
+delta_e2000_test!(de2000_7, (50.0, 0.0, 0.0), (50.0, -1.0, 2.0), 2.3669);
+delta_e2000_test!(de2000_17, (50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492);
+delta_e2000_test!(de2000_18, (50.0, 2.5, 0.0), (61.0, -5.0, 29.0), 22.8977);
+delta_e2000_test!(de2000_19, (50.0, 2.5, 0.0), (56.0, -27.0, -3.0), 31.9030);
+delta_e2000_test!(de2000_20, (50.0, 2.5, 0.0), (58.0, 24.0, 15.0), 19.4535);
+delta_e2000_test!(
+    de2000_25,
+    (60.2574, -34.0099, 36.2677),
+    (60.4626, -34.1751, 39.4387),
+    1.2644
+);
+
+#[test]
+fn identical_colors_have_no_difference() {
+    let color = lab(42.0, -12.0, 30.0);
+    assert_eq!(0.0, color.delta_e76(&color));
+    assert_eq!(0.0, color.delta_e94(&color));
+    assert_eq!(0.0, color.delta_e2000(&color));
+}
+
+#[test]
+fn converter_uses_white_reference_luminance() {
+    let white = AllChannelMeasurement {
+        red: 2000,
+        green: 2000,
+        blue: 2000,
+        clear: 6000,
+    };
+    let half = AllChannelMeasurement {
+        red: 1000,
+        green: 1000,
+        blue: 1000,
+        clear: 3000,
+    };
+    let mut converter =
+        LabConverter::new(CalibrationMatrix::tcs3414_approximation(), Illuminant::D65);
+    converter.capture_white_reference(&white);
+    assert_close(100.0, converter.to_lab(&white).l, 1e-3);
+    assert_close(100.0, converter.to_luv(&white).l, 1e-3);
+    assert_close(76.07, converter.to_lab(&half).l, 0.01);
+    assert_close(76.07, converter.to_luv(&half).l, 0.01);
+}
+
+#[test]
+fn converter_scales_white_point() {
+    let converter = LabConverter::new(CalibrationMatrix::tcs3414_approximation(), Illuminant::A)
+        .with_white_luminance(50.0);
+    let white = converter.white();
+    assert_close(54.925, white.x, 1e-3);
+    assert_close(50.0, white.y, 1e-3);
+    assert_close(17.7925, white.z, 1e-3);
+}
@@ -0,0 +1,84 @@
+use tcs3472::{AllChannelMeasurement, Illuminance, LuxCoefficients, RgbCGain};
+
+const MEASUREMENT: AllChannelMeasurement = AllChannelMeasurement {
+    red: 1000,
+    green: 1500,
+    blue: 800,
+    clear: 3000,
+};
+
+#[test]
+fn can_calculate_lux_and_cct() {
+    let result = LuxCoefficients::default().calculate(&MEASUREMENT, RgbCGain::_4x, 42);
+    assert_eq!(
+        Illuminance {
+            millilux: 904_935,
+            color_temperature: Some(4304),
+        },
+        result
+    );
+    assert!((result.lux() - 904.935).abs() < 0.001);
+}
+
+#[test]
+fn lux_scales_with_gain_and_integration_time() {
+    let coeffs = LuxCoefficients::default();
+    let low = coeffs.calculate(&MEASUREMENT, RgbCGain::_16x, 42);
+    let high = coeffs.calculate(&MEASUREMENT, RgbCGain::_4x, 84);
+    assert_eq!(226_233, low.millilux);
+    assert_eq!(452_467, high.millilux);
+}
+
+#[test]
+fn glass_attenuation_increases_lux() {
+    let coeffs = LuxCoefficients {
+        glass_attenuation: 2000,
+        ..Default::default()
+    };
+    let result = coeffs.calculate(&MEASUREMENT, RgbCGain::_4x, 42);
+    assert_eq!(1_809_871, result.millilux);
+}
+
+#[test]
+fn cct_is_unknown_without_red() {
+    let m = AllChannelMeasurement {
+        red: 0,
+        green: 100,
+        blue: 100,
+        clear: 200,
+    };
+    let result = LuxCoefficients::default().calculate(&m, RgbCGain::_1x, 1);
+    assert_eq!(None, result.color_temperature);
+}
+
+#[test]
+fn dark_measurement_is_zero_lux() {
+    let m = AllChannelMeasurement {
+        red: 0,
+        green: 0,
+        blue: 0,
+        clear: 0,
+    };
+    let result = LuxCoefficients::default().calculate(&m, RgbCGain::_60x, 256);
+    assert_eq!(0, result.millilux);
+}
+
+#[test]
+fn extreme_coefficients_saturate() {
+    let coeffs = LuxCoefficients {
+        red: i32::MAX,
+        green: i32::MAX,
+        blue: i32::MAX,
+        glass_attenuation: u32::MAX,
+        device_factor: u32::MAX,
+        ..Default::default()
+    };
+    let m = AllChannelMeasurement {
+        red: 20000,
+        green: 20000,
+        blue: 20000,
+        clear: 65535,
+    };
+    let result = coeffs.calculate(&m, RgbCGain::_1x, 1);
+    assert_eq!(u32::MAX, result.millilux);
+}
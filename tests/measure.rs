@@ -0,0 +1,98 @@
+mod common;
+use crate::common::{destroy, m, new, read, read_all_channels, write, BitFlags, Register};
+use embedded_hal_mock::eh1::{
+    delay::{CheckedDelay, Transaction as DelayTrans},
+    i2c::Transaction as I2cTrans,
+};
+use tcs3472::Error;
+
+fn start() -> Vec<I2cTrans> {
+    vec![
+        write(Register::ENABLE, BitFlags::POWER_ON),
+        write(Register::ENABLE, BitFlags::POWER_ON | BitFlags::RGBC_EN),
+    ]
+}
+
+fn status(valid: bool) -> I2cTrans {
+    let status = if valid { BitFlags::RGBC_VALID } else { 0 };
+    read(Register::STATUS, status)
+}
+
+fn read_all() -> I2cTrans {
+    read_all_channels(&m(0x4567, 0x89AB, 0xCDEF, 0x0123))
+}
+
+#[test]
+fn can_measure() {
+    let transactions = [
+        vec![read(Register::ATIME, 0xFF)],
+        start(),
+        vec![status(true), read_all()],
+    ]
+    .concat();
+    let mut dev = new(&transactions);
+    let mut delay = CheckedDelay::new(&[DelayTrans::delay_us(2400), DelayTrans::delay_us(2400)]);
+    let m = dev.measure(&mut delay, false).unwrap();
+    assert_eq!(0x0123, m.clear);
+    assert_eq!(0x4567, m.red);
+    assert_eq!(0x89AB, m.green);
+    assert_eq!(0xCDEF, m.blue);
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn can_measure_and_power_down() {
+    let mut transactions = [
+        vec![write(Register::ATIME, 0xF6)],
+        start(),
+        vec![status(false), status(true), read_all()],
+    ]
+    .concat();
+    transactions.push(write(Register::ENABLE, 0));
+    let mut dev = new(&transactions);
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::delay_us(2400),
+        DelayTrans::delay_us(24000),
+        DelayTrans::delay_us(2400),
+    ]);
+    dev.set_integration_cycles(10).unwrap();
+    dev.measure(&mut delay, true).unwrap();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn reads_unknown_integration_time_before_measuring() {
+    let transactions = [
+        vec![read(Register::ATIME, 0x00)],
+        start(),
+        vec![status(true), read_all()],
+    ]
+    .concat();
+    let mut dev = new(&transactions);
+    let mut delay = CheckedDelay::new(&[DelayTrans::delay_us(2400), DelayTrans::delay_us(614_400)]);
+    dev.measure(&mut delay, false).unwrap();
+    assert_eq!(65535, dev.full_scale_count());
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn measure_times_out() {
+    let mut transactions = [vec![read(Register::ATIME, 0xFF)], start()].concat();
+    let mut delays = vec![DelayTrans::delay_us(2400), DelayTrans::delay_us(2400)];
+    transactions.push(status(false));
+    for _ in 0..4 {
+        transactions.push(status(false));
+        delays.push(DelayTrans::delay_us(2400));
+    }
+    let mut dev = new(&transactions);
+    let mut delay = CheckedDelay::new(&delays);
+    match dev.measure(&mut delay, false) {
+        Err(Error::Timeout) => (),
+        _ => panic!(),
+    }
+    delay.done();
+    destroy(dev);
+}
@@ -0,0 +1,305 @@
+mod common;
+use crate::common::{apply_config, m, read, read_all_channels, write, BitFlags as BF, Register};
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::{
+    delay::{CheckedDelay, Transaction as DelayTrans},
+    i2c::{Mock as I2cMock, Transaction as I2cTrans},
+};
+use tcs3472::{Config, Error, I2cMux, MuxChannel, RgbCGain, SensorArray, Tcs3472};
+
+const MUX_ADDR: u8 = 0x70;
+
+fn select(channel: u8) -> I2cTrans {
+    I2cTrans::write(MUX_ADDR, vec![1 << channel])
+}
+
+fn read_atime(atime: u8) -> I2cTrans {
+    read(Register::ATIME, atime)
+}
+
+fn read_status(status: u8) -> I2cTrans {
+    read(Register::STATUS, status)
+}
+
+fn read_sensor(clear: u16) -> I2cTrans {
+    read_all_channels(&m(2, 3, 4, clear))
+}
+
+#[test]
+fn channel_out_of_range_is_rejected() {
+    let mux = I2cMux::new(I2cMock::new(&[]));
+    assert!(mux.channel(7).is_some());
+    assert!(mux.channel(8).is_none());
+    mux.destroy().done();
+}
+
+#[test]
+fn channel_can_be_copied_for_any_bus() {
+    struct NotCloneable;
+    fn assert_copy<T: Copy>() {}
+    assert_copy::<MuxChannel<'static, NotCloneable>>();
+}
+
+#[test]
+fn selects_channel_only_when_it_changes() {
+    let mux = I2cMux::new_with_address(
+        I2cMock::new(&[
+            I2cTrans::write(0x74, vec![1 << 2]),
+            write(Register::ENABLE, BF::POWER_ON),
+            write(Register::CONTROL, 2),
+            I2cTrans::write(0x74, vec![1 << 5]),
+            write(Register::ENABLE, BF::POWER_ON),
+            I2cTrans::write(0x74, vec![1 << 2]),
+            write(Register::ENABLE, BF::POWER_ON | BF::RGBC_EN),
+        ]),
+        0x74,
+    );
+    let mut first = Tcs3472::new(mux.channel(2).unwrap());
+    let mut second = Tcs3472::new(mux.channel(5).unwrap());
+    first.enable().unwrap();
+    first.set_rgbc_gain(RgbCGain::_16x).unwrap();
+    second.enable().unwrap();
+    first.enable_rgbc().unwrap();
+    mux.destroy().done();
+}
+
+#[test]
+fn selects_channel_again_after_failed_selection() {
+    let mux = I2cMux::new(I2cMock::new(&[
+        select(0).with_error(ErrorKind::Other),
+        select(0),
+        write(Register::ENABLE, BF::POWER_ON),
+    ]));
+    let mut sensor = Tcs3472::new(mux.channel(0).unwrap());
+    assert!(matches!(sensor.enable(), Err(Error::I2C(ErrorKind::Other))));
+    sensor.enable().unwrap();
+    mux.destroy().done();
+}
+
+#[test]
+fn can_apply_common_config() {
+    let config = Config::builder().gain(RgbCGain::_60x).build().unwrap();
+    let mut transactions = vec![select(0)];
+    transactions.extend(apply_config(&config));
+    transactions.push(select(1));
+    transactions.extend(apply_config(&config));
+    let mux = I2cMux::new(I2cMock::new(&transactions));
+    let mut array = SensorArray::new([
+        Tcs3472::new(mux.channel(0).unwrap()),
+        Tcs3472::new(mux.channel(1).unwrap()),
+    ]);
+    array.apply_config(&config).unwrap();
+    mux.destroy().done();
+}
+
+#[test]
+fn can_apply_config_per_sensor() {
+    let configs = [
+        Config::builder().gain(RgbCGain::_4x).build().unwrap(),
+        Config::builder().integration_cycles(10).build().unwrap(),
+    ];
+    let mut transactions = vec![select(0)];
+    transactions.extend(apply_config(&configs[0]));
+    transactions.push(select(1));
+    transactions.extend(apply_config(&configs[1]));
+    let mux = I2cMux::new(I2cMock::new(&transactions));
+    let mut array = SensorArray::new([
+        Tcs3472::new(mux.channel(0).unwrap()),
+        Tcs3472::new(mux.channel(1).unwrap()),
+    ]);
+    array.apply_configs(&configs).unwrap();
+    mux.destroy().done();
+}
+
+#[test]
+fn can_read_all_sensors() {
+    let mux = I2cMux::new(I2cMock::new(&[
+        select(3),
+        read_sensor(10),
+        select(1),
+        read_sensor(20),
+        select(6),
+        read_sensor(30),
+    ]));
+    let mut array = SensorArray::new([
+        Tcs3472::new(mux.channel(3).unwrap()),
+        Tcs3472::new(mux.channel(1).unwrap()),
+        Tcs3472::new(mux.channel(6).unwrap()),
+    ]);
+    let measurements = array.read_all_channels().unwrap();
+    assert_eq!([10, 20, 30], measurements.map(|m| m.clear));
+    assert_eq!(2, measurements[2].red);
+    mux.destroy().done();
+}
+
+#[test]
+fn reports_index_of_failing_sensor() {
+    let mux = I2cMux::new(I2cMock::new(&[
+        select(0),
+        read_sensor(10),
+        select(1),
+        read_sensor(20).with_error(ErrorKind::Other),
+    ]));
+    let mut array = SensorArray::new([
+        Tcs3472::new(mux.channel(0).unwrap()),
+        Tcs3472::new(mux.channel(1).unwrap()),
+    ]);
+    let error = array.read_all_channels().unwrap_err();
+    assert_eq!(1, error.index);
+    assert!(matches!(error.error, Error::I2C(ErrorKind::Other)));
+    assert!(array.sensor_mut(2).is_none());
+    mux.destroy().done();
+}
+
+#[test]
+fn can_trigger_all_sensors() {
+    let mux = I2cMux::new(I2cMock::new(&[
+        select(0),
+        read_atime(0xFF),
+        write(Register::ENABLE, BF::POWER_ON),
+        select(1),
+        read_atime(0xFF),
+        write(Register::ENABLE, BF::POWER_ON),
+        select(0),
+        write(Register::ENABLE, BF::POWER_ON | BF::RGBC_EN),
+        select(1),
+        write(Register::ENABLE, BF::POWER_ON | BF::RGBC_EN),
+        select(0),
+        read_status(BF::RGBC_VALID),
+        read_sensor(10),
+        write(Register::ENABLE, 0),
+        select(1),
+        read_status(0),
+        read_status(BF::RGBC_VALID),
+        read_sensor(20),
+        write(Register::ENABLE, 0),
+    ]));
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::delay_us(2400),
+        DelayTrans::delay_us(2400),
+        DelayTrans::delay_us(2400),
+    ]);
+    let mut array = SensorArray::new([
+        Tcs3472::new(mux.channel(0).unwrap()),
+        Tcs3472::new(mux.channel(1).unwrap()),
+    ]);
+    let mut time = 0;
+    let timestamp = || {
+        time += 7;
+        time
+    };
+    let results = array.trigger(&mut delay, timestamp, true).unwrap();
+    assert_eq!([10, 20], results.map(|r| r.measurement.clear));
+    assert_eq!([7, 14], results.map(|r| r.started_at));
+    assert_eq!([21, 28], results.map(|r| r.read_at));
+    assert_eq!([0, 7], results.map(|r| r.skew));
+    mux.destroy().done();
+    delay.done();
+}
+
+#[test]
+fn trigger_waits_for_longest_integration_time() {
+    let mux = I2cMux::new(I2cMock::new(&[
+        select(0),
+        write(Register::ATIME, 0xF6),
+        write(Register::ENABLE, BF::POWER_ON),
+        select(1),
+        read_atime(0xFF),
+        write(Register::ENABLE, BF::POWER_ON),
+        select(0),
+        write(Register::ENABLE, BF::POWER_ON | BF::RGBC_EN),
+        select(1),
+        write(Register::ENABLE, BF::POWER_ON | BF::RGBC_EN),
+        select(0),
+        read_status(BF::RGBC_VALID),
+        read_sensor(10),
+        select(1),
+        read_status(BF::RGBC_VALID),
+        read_sensor(20),
+    ]));
+    let mut delay = CheckedDelay::new(&[DelayTrans::delay_us(2400), DelayTrans::delay_us(24000)]);
+    let mut array = SensorArray::new([
+        Tcs3472::new(mux.channel(0).unwrap()),
+        Tcs3472::new(mux.channel(1).unwrap()),
+    ]);
+    array
+        .sensor_mut(0)
+        .unwrap()
+        .set_integration_cycles(10)
+        .unwrap();
+    array.trigger(&mut delay, || 0, false).unwrap();
+    mux.destroy().done();
+    delay.done();
+}
+
+#[test]
+fn trigger_reads_unknown_integration_time() {
+    let mux = I2cMux::new(I2cMock::new(&[
+        select(0),
+        write(Register::ATIME, 0xF6),
+        write(Register::ENABLE, BF::POWER_ON),
+        select(1),
+        read_atime(0x00),
+        write(Register::ENABLE, BF::POWER_ON),
+        select(0),
+        write(Register::ENABLE, BF::POWER_ON | BF::RGBC_EN),
+        select(1),
+        write(Register::ENABLE, BF::POWER_ON | BF::RGBC_EN),
+        select(0),
+        read_status(BF::RGBC_VALID),
+        read_sensor(10),
+        select(1),
+        read_status(BF::RGBC_VALID),
+        read_sensor(20),
+    ]));
+    let mut delay = CheckedDelay::new(&[DelayTrans::delay_us(2400), DelayTrans::delay_us(614_400)]);
+    let mut array = SensorArray::new([
+        Tcs3472::new(mux.channel(0).unwrap()),
+        Tcs3472::new(mux.channel(1).unwrap()),
+    ]);
+    array
+        .sensor_mut(0)
+        .unwrap()
+        .set_integration_cycles(10)
+        .unwrap();
+    array.trigger(&mut delay, || 0, false).unwrap();
+    mux.destroy().done();
+    delay.done();
+}
+
+#[test]
+fn trigger_reports_timeout_of_sensor() {
+    let mut transactions = vec![
+        select(0),
+        read_atime(0xFF),
+        write(Register::ENABLE, BF::POWER_ON),
+        select(1),
+        read_atime(0xFF),
+        write(Register::ENABLE, BF::POWER_ON),
+        select(0),
+        write(Register::ENABLE, BF::POWER_ON | BF::RGBC_EN),
+        select(1),
+        write(Register::ENABLE, BF::POWER_ON | BF::RGBC_EN),
+        select(0),
+        read_status(BF::RGBC_VALID),
+        read_sensor(10),
+        select(1),
+    ];
+    let mut delays = vec![DelayTrans::delay_us(2400), DelayTrans::delay_us(2400)];
+    for _ in 0..4 {
+        transactions.push(read_status(0));
+        delays.push(DelayTrans::delay_us(2400));
+    }
+    transactions.push(read_status(0));
+    let mux = I2cMux::new(I2cMock::new(&transactions));
+    let mut delay = CheckedDelay::new(&delays);
+    let mut array = SensorArray::new([
+        Tcs3472::new(mux.channel(0).unwrap()),
+        Tcs3472::new(mux.channel(1).unwrap()),
+    ]);
+    let error = array.trigger(&mut delay, || 0, false).unwrap_err();
+    assert_eq!(1, error.index);
+    assert!(matches!(error.error, Error::Timeout));
+    mux.destroy().done();
+    delay.done();
+}
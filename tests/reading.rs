@@ -1,6 +1,7 @@
 mod common;
-use crate::common::{destroy, new, BitFlags, Register, DEV_ADDR};
+use crate::common::{destroy, new, new_with_variant, BitFlags, Register, DEV_ADDR, DEV_ADDR_ALT};
 use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use tcs3472::{DeviceVariant, Error};
 
 #[test]
 fn can_read_rgbc_status_not_valid() {
@@ -71,3 +72,150 @@ fn can_read_device_id() {
     assert_eq!(0x44, dev.read_device_id().unwrap());
     destroy(dev);
 }
+
+#[test]
+fn can_read_status() {
+    let mut dev = new(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![BitFlags::CMD | Register::STATUS],
+        vec![BitFlags::RGBC_INT | BitFlags::RGBC_VALID],
+    )]);
+    let status = dev.read_status().unwrap();
+    assert!(status.rgbc_interrupt);
+    assert!(status.rgbc_valid);
+    destroy(dev);
+}
+
+#[test]
+fn can_read_status_no_interrupt() {
+    let mut dev = new(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![BitFlags::CMD | Register::STATUS],
+        vec![BitFlags::RGBC_VALID],
+    )]);
+    let status = dev.read_status().unwrap();
+    assert!(!status.rgbc_interrupt);
+    assert!(status.rgbc_valid);
+    destroy(dev);
+}
+
+#[test]
+fn can_clear_rgbc_interrupt() {
+    let mut dev = new(&[I2cTrans::write(
+        DEV_ADDR,
+        vec![BitFlags::CMD | BitFlags::CMD_SPECIAL_FN | BitFlags::RGBC_INT_CLEAR],
+    )]);
+    dev.clear_rgbc_interrupt().unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_service_rgbc_interrupt() {
+    let mut dev = new(&[
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![BitFlags::CMD | BitFlags::CMD_AUTO_INC | Register::STATUS],
+            vec![
+                BitFlags::RGBC_INT | BitFlags::RGBC_VALID,
+                0x23,
+                0x01,
+                0x67,
+                0x45,
+                0xAB,
+                0x89,
+                0xEF,
+                0xCD,
+            ],
+        ),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![BitFlags::CMD | BitFlags::CMD_SPECIAL_FN | BitFlags::RGBC_INT_CLEAR],
+        ),
+    ]);
+    let (status, measurement) = dev.service_rgbc_interrupt().unwrap();
+    assert!(status.rgbc_interrupt);
+    assert!(status.rgbc_valid);
+    assert_eq!(0x0123, measurement.clear);
+    assert_eq!(0x4567, measurement.red);
+    assert_eq!(0x89AB, measurement.green);
+    assert_eq!(0xCDEF, measurement.blue);
+    destroy(dev);
+}
+
+macro_rules! read_variant_test {
+    ($name:ident, $variant:ident, $address:expr, $id:expr) => {
+        #[test]
+        fn $name() {
+            let mut dev = new_with_variant(
+                &[I2cTrans::write_read(
+                    $address,
+                    vec![BitFlags::CMD | Register::ID],
+                    vec![$id],
+                )],
+                DeviceVariant::$variant,
+            );
+            assert_eq!(DeviceVariant::$variant, dev.probe().unwrap());
+            destroy(dev);
+        }
+    };
+}
+
+read_variant_test!(can_probe_tcs34721, Tcs34721, DEV_ADDR_ALT, 0x44);
+read_variant_test!(can_probe_tcs34723, Tcs34723, DEV_ADDR_ALT, 0x4D);
+read_variant_test!(can_probe_tcs34725, Tcs34725, DEV_ADDR, 0x44);
+read_variant_test!(can_probe_tcs34727, Tcs34727, DEV_ADDR, 0x4D);
+
+#[test]
+fn can_read_device_variant_at_default_address() {
+    let mut dev = new(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![BitFlags::CMD | Register::ID],
+        vec![0x4D],
+    )]);
+    assert_eq!(DeviceVariant::Tcs34727, dev.read_device_variant().unwrap());
+    destroy(dev);
+}
+
+#[test]
+fn probe_fails_for_other_variant() {
+    let mut dev = new_with_variant(
+        &[I2cTrans::write_read(
+            DEV_ADDR,
+            vec![BitFlags::CMD | Register::ID],
+            vec![0x4D],
+        )],
+        DeviceVariant::Tcs34725,
+    );
+    match dev.probe() {
+        Err(Error::UnexpectedDeviceId) => (),
+        _ => panic!(),
+    }
+    destroy(dev);
+}
+
+#[test]
+fn probe_fails_for_unknown_id() {
+    let mut dev = new(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![BitFlags::CMD | Register::ID],
+        vec![0x12],
+    )]);
+    match dev.probe() {
+        Err(Error::UnexpectedDeviceId) => (),
+        _ => panic!(),
+    }
+    destroy(dev);
+}
+
+#[test]
+fn variant_uses_its_address() {
+    let mut dev = new_with_variant(
+        &[I2cTrans::write(
+            DEV_ADDR_ALT,
+            vec![BitFlags::CMD | Register::ENABLE, BitFlags::POWER_ON],
+        )],
+        DeviceVariant::Tcs34721,
+    );
+    dev.enable().unwrap();
+    destroy(dev);
+}
@@ -0,0 +1,184 @@
+mod common;
+use crate::common::{destroy, new, BitFlags as BF, Register, DEV_ADDR};
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use tcs3472::{
+    registers::{Atime, ConfigRegister, Control, Enable, Pers, Status, TypedRegister, Wtime},
+    Error, RgbCGain, RgbCInterruptPersistence,
+};
+
+#[test]
+fn register_addresses() {
+    assert_eq!(Register::ENABLE, Enable::ADDRESS);
+    assert_eq!(Register::ATIME, Atime::ADDRESS);
+    assert_eq!(Register::WTIME, Wtime::ADDRESS);
+    assert_eq!(Register::APERS, Pers::ADDRESS);
+    assert_eq!(Register::CONFIG, ConfigRegister::ADDRESS);
+    assert_eq!(Register::CONTROL, Control::ADDRESS);
+    assert_eq!(Register::STATUS, Status::ADDRESS);
+}
+
+macro_rules! encode_decode_test {
+    ($name:ident, $value:expr, $bits:expr) => {
+        #[test]
+        fn $name() {
+            let value = $value;
+            assert_eq!($bits, value.encode());
+            assert_eq!(Some(value), TypedRegister::decode($bits));
+        }
+    };
+}
+
+encode_decode_test!(
+    enable,
+    Enable {
+        power_on: true,
+        rgbc: false,
+        wait: true,
+        rgbc_interrupts: true
+    },
+    BF::POWER_ON | BF::WAIT_EN | BF::RGBC_INT_EN
+);
+encode_decode_test!(atime_1, Atime::new(1).unwrap(), 0xFF);
+encode_decode_test!(atime_256, Atime::new(256).unwrap(), 0);
+encode_decode_test!(wtime, Wtime::new(42).unwrap(), 0xD6);
+encode_decode_test!(
+    pers,
+    Pers {
+        persistence: RgbCInterruptPersistence::_60
+    },
+    0x0F
+);
+encode_decode_test!(config, ConfigRegister { wait_long: true }, BF::WLONG);
+encode_decode_test!(
+    control,
+    Control {
+        gain: RgbCGain::_60x
+    },
+    3
+);
+encode_decode_test!(
+    status,
+    Status {
+        rgbc_interrupt: true,
+        rgbc_valid: true
+    },
+    BF::RGBC_INT | BF::RGBC_VALID
+);
+
+#[test]
+fn cannot_create_invalid_cycles() {
+    assert_eq!(None, Atime::new(0));
+    assert_eq!(None, Atime::new(257));
+    assert_eq!(None, Wtime::new(0));
+    assert_eq!(None, Wtime::new(257));
+}
+
+#[test]
+fn reserved_bits_are_ignored() {
+    assert_eq!(
+        Some(Control {
+            gain: RgbCGain::_4x
+        }),
+        Control::decode(0xF5)
+    );
+    assert_eq!(
+        Some(Pers {
+            persistence: RgbCInterruptPersistence::_5
+        }),
+        Pers::decode(0x24)
+    );
+}
+
+#[test]
+fn defaults_are_power_on_values() {
+    assert_eq!(0, Enable::default().encode());
+    assert_eq!(0xFF, Atime::default().encode());
+    assert_eq!(0xFF, Wtime::default().encode());
+    assert_eq!(0, Pers::default().encode());
+    assert_eq!(0, ConfigRegister::default().encode());
+    assert_eq!(0, Control::default().encode());
+}
+
+#[test]
+fn can_write_typed_register() {
+    let mut dev = new(&[I2cTrans::write(
+        DEV_ADDR,
+        vec![BF::CMD | Register::WTIME, 0xD6],
+    )]);
+    dev.write_typed_register(Wtime::new(42).unwrap()).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_read_typed_register() {
+    let mut dev = new(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![BF::CMD | Register::CONTROL],
+        vec![2],
+    )]);
+    let control: Control = dev.read_typed_register().unwrap();
+    assert_eq!(RgbCGain::_16x, control.gain);
+    destroy(dev);
+}
+
+#[test]
+fn read_typed_register_ignores_reserved_bits() {
+    let mut dev = new(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![BF::CMD | Register::APERS],
+        vec![0x20],
+    )]);
+    let pers: Pers = dev.read_typed_register().unwrap();
+    assert_eq!(RgbCInterruptPersistence::Every, pers.persistence);
+    destroy(dev);
+}
+
+#[test]
+fn can_read_and_write_raw_register() {
+    let mut dev = new(&[
+        I2cTrans::write_read(DEV_ADDR, vec![BF::CMD | 0x1C], vec![0xAB]),
+        I2cTrans::write(DEV_ADDR, vec![BF::CMD | 0x1C, 0xCD]),
+    ]);
+    assert_eq!(0xAB, dev.read_register(0x1C).unwrap());
+    dev.write_register(0x1C, 0xCD).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn raw_register_address_is_checked() {
+    let mut dev = new(&[]);
+    assert!(matches!(
+        dev.read_register(0x20),
+        Err(Error::InvalidInputData)
+    ));
+    assert!(matches!(
+        dev.write_register(0x20, 0),
+        Err(Error::InvalidInputData)
+    ));
+    destroy(dev);
+}
+
+#[test]
+fn raw_enable_write_is_stored() {
+    let mut dev = new(&[
+        I2cTrans::write(DEV_ADDR, vec![BF::CMD | Register::ENABLE, BF::WAIT_EN]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![BF::CMD | Register::ENABLE, BF::WAIT_EN | BF::POWER_ON],
+        ),
+    ]);
+    dev.write_register(Register::ENABLE, BF::WAIT_EN).unwrap();
+    dev.enable().unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn raw_integration_time_write_is_stored() {
+    let mut dev = new(&[I2cTrans::write(
+        DEV_ADDR,
+        vec![BF::CMD | Register::ATIME, 0xFC],
+    )]);
+    dev.write_register(Register::ATIME, 0xFC).unwrap();
+    assert_eq!(4096, dev.full_scale_count());
+    destroy(dev);
+}
@@ -0,0 +1,141 @@
+mod common;
+use crate::common::{apply_config, destroy, new, read_burst, write, BitFlags as BF, Register};
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use tcs3472::{Config, DeviceReset, EnableFlags, Error, RgbCGain};
+
+const ENABLE: u8 = BF::POWER_ON | BF::RGBC_EN;
+
+fn read_enable_atime(enable: u8, atime: u8) -> I2cTrans {
+    read_burst(Register::ENABLE, &[enable, atime])
+}
+
+fn restore() -> Vec<I2cTrans> {
+    vec![
+        write(Register::ATIME, 0xF6),
+        write(Register::WTIME, 0xFF),
+        write(Register::AILTL, 0x00),
+        write(Register::AILTL + 1, 0x04),
+        write(Register::AIHTL, 0x00),
+        write(Register::AIHTL + 1, 0xF0),
+        write(Register::APERS, 0),
+        write(Register::CONFIG, 0),
+        write(Register::CONTROL, 2),
+        write(Register::ENABLE, ENABLE),
+    ]
+}
+
+fn config() -> Config {
+    Config::builder()
+        .gain(RgbCGain::_16x)
+        .integration_cycles(10)
+        .rgbc_interrupt_low_threshold(1024)
+        .rgbc_interrupt_high_threshold(61440)
+        .enable_flags(EnableFlags {
+            power_on: true,
+            rgbc: true,
+            ..Default::default()
+        })
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn no_reset_if_registers_match() {
+    let transactions = [
+        apply_config(&config()),
+        vec![read_enable_atime(ENABLE, 0xF6)],
+    ]
+    .concat();
+    let mut dev = new(&transactions);
+    dev.apply_config(&config()).unwrap();
+    assert_eq!(None, dev.recover_from_reset().unwrap());
+    destroy(dev);
+}
+
+#[test]
+fn no_reset_detected_if_nothing_known() {
+    let mut dev = new(&[read_enable_atime(0, 0xFF)]);
+    assert_eq!(None, dev.recover_from_reset().unwrap());
+    destroy(dev);
+}
+
+#[test]
+fn restores_configuration_after_reset() {
+    let transactions = [
+        apply_config(&config()),
+        vec![read_enable_atime(0, 0xFF)],
+        restore(),
+    ]
+    .concat();
+    let mut dev = new(&transactions);
+    dev.apply_config(&config()).unwrap();
+    assert_eq!(
+        Some(DeviceReset {
+            enable: EnableFlags::default(),
+            integration_cycles: 1
+        }),
+        dev.recover_from_reset().unwrap()
+    );
+    assert_eq!(Some(config()), dev.cached_config());
+    // the restored values are known again
+    dev.set_rgbc_gain(RgbCGain::_16x).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn restores_configuration_again_after_failed_restore() {
+    let transactions = [
+        apply_config(&config()),
+        vec![
+            read_enable_atime(0, 0xFF),
+            write(Register::ATIME, 0xF6),
+            write(Register::WTIME, 0xFF).with_error(ErrorKind::Other),
+            read_enable_atime(0, 0xFF),
+        ],
+        restore(),
+    ]
+    .concat();
+    let mut dev = new(&transactions);
+    dev.apply_config(&config()).unwrap();
+    assert!(matches!(
+        dev.recover_from_reset(),
+        Err(Error::I2C(ErrorKind::Other))
+    ));
+    assert_eq!(Some(config()), dev.cached_config());
+    assert!(dev.recover_from_reset().unwrap().is_some());
+    assert_eq!(Some(config()), dev.cached_config());
+    destroy(dev);
+}
+
+#[test]
+fn restores_only_known_registers() {
+    let mut dev = new(&[
+        write(Register::ENABLE, BF::POWER_ON),
+        write(Register::ENABLE, ENABLE),
+        read_enable_atime(0, 0xFF),
+        write(Register::ENABLE, ENABLE),
+    ]);
+    dev.enable().unwrap();
+    dev.enable_rgbc().unwrap();
+    assert!(dev.recover_from_reset().unwrap().is_some());
+    destroy(dev);
+}
+
+#[test]
+fn detects_integration_time_reset() {
+    let mut dev = new(&[
+        write(Register::ATIME, 0xC0),
+        read_enable_atime(0, 0xFF),
+        write(Register::ATIME, 0xC0),
+    ]);
+    dev.set_integration_cycles(64).unwrap();
+    assert_eq!(
+        Some(DeviceReset {
+            enable: EnableFlags::default(),
+            integration_cycles: 1
+        }),
+        dev.recover_from_reset().unwrap()
+    );
+    destroy(dev);
+}
@@ -0,0 +1,122 @@
+mod common;
+use crate::common::m;
+use tcs3472::{GamutClipping, LinearRgb, Srgb8, WhiteBalance};
+
+#[test]
+fn divides_by_clear_without_white_reference() {
+    let rgb = WhiteBalance::new().to_linear_rgb(&m(250, 500, 125, 1000));
+    assert_eq!(
+        LinearRgb {
+            red: 0.25,
+            green: 0.5,
+            blue: 0.125
+        },
+        rgb
+    );
+}
+
+#[test]
+fn white_reference_becomes_white() {
+    let white = m(3000, 4000, 5000, 12000);
+    let mut balance = WhiteBalance::new();
+    balance.capture_white_reference(&white);
+    assert_eq!(
+        Srgb8 {
+            red: 255,
+            green: 255,
+            blue: 255
+        },
+        balance.to_srgb8(&white)
+    );
+    let rgb = balance.to_linear_rgb(&m(1500, 1000, 5000, 7500));
+    assert_eq!(0.5, rgb.red);
+    assert_eq!(0.25, rgb.green);
+    assert_eq!(1.0, rgb.blue);
+}
+
+#[test]
+fn dark_reference_is_subtracted() {
+    let mut balance = WhiteBalance::new();
+    balance.capture_dark_reference(&m(100, 100, 100, 300));
+    balance.capture_white_reference(&m(1100, 2100, 3100, 6300));
+    let rgb = balance.to_linear_rgb(&m(600, 600, 50, 1250));
+    assert_eq!(0.5, rgb.red);
+    assert_eq!(0.25, rgb.green);
+    assert_eq!(0.0, rgb.blue);
+}
+
+#[test]
+fn can_encode_srgb() {
+    let rgb = LinearRgb {
+        red: 0.0,
+        green: 0.2,
+        blue: 0.002,
+    };
+    assert_eq!(
+        Srgb8 {
+            red: 0,
+            green: 124,
+            blue: 7
+        },
+        rgb.to_srgb8()
+    );
+}
+
+macro_rules! clip_test {
+    ($name:ident, $clipping:ident, $r:expr, $g:expr, $b:expr) => {
+        #[test]
+        fn $name() {
+            let mut balance = WhiteBalance::new().with_clipping(GamutClipping::$clipping);
+            balance.capture_white_reference(&m(1000, 1000, 1000, 3000));
+            let rgb = balance.to_linear_rgb(&m(2000, 1000, 250, 3250));
+            assert_eq!(
+                LinearRgb {
+                    red: $r,
+                    green: $g,
+                    blue: $b
+                },
+                rgb
+            );
+        }
+    };
+}
+
+clip_test!(can_clamp, Clamp, 1.0, 1.0, 0.25);
+clip_test!(can_scale, Scale, 1.0, 0.5, 0.125);
+clip_test!(can_normalize, Normalize, 1.0, 0.5, 0.125);
+
+#[test]
+fn normalize_brightens_dark_colors() {
+    let rgb = LinearRgb {
+        red: 0.1,
+        green: 0.05,
+        blue: 0.0,
+    }
+    .clip(GamutClipping::Normalize);
+    assert_eq!(1.0, rgb.red);
+    assert_eq!(0.5, rgb.green);
+}
+
+#[test]
+fn scale_keeps_colors_in_range() {
+    let rgb = LinearRgb {
+        red: 0.1,
+        green: 0.05,
+        blue: 0.0,
+    };
+    assert_eq!(rgb, rgb.clip(GamutClipping::Scale));
+}
+
+#[test]
+fn dark_measurement_is_black() {
+    let mut balance = WhiteBalance::new();
+    balance.capture_dark_reference(&m(100, 100, 100, 300));
+    assert_eq!(
+        Srgb8 {
+            red: 0,
+            green: 0,
+            blue: 0
+        },
+        balance.to_srgb8(&m(50, 50, 50, 150))
+    );
+}
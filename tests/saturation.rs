@@ -0,0 +1,92 @@
+mod common;
+use crate::common::{destroy, new, BitFlags, Register, DEV_ADDR};
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use tcs3472::{AllChannelMeasurement, ChannelSaturation, Saturation};
+
+#[test]
+fn full_scale_defaults_to_one_cycle() {
+    let dev = new(&[]);
+    assert_eq!(1024, dev.full_scale_count());
+    destroy(dev);
+}
+
+macro_rules! full_scale_test {
+    ($name:ident, $cycles:expr, $atime:expr, $expected:expr) => {
+        #[test]
+        fn $name() {
+            let mut dev = new(&[I2cTrans::write(
+                DEV_ADDR,
+                vec![BitFlags::CMD | Register::ATIME, $atime],
+            )]);
+            dev.set_integration_cycles($cycles).unwrap();
+            assert_eq!($expected, dev.full_scale_count());
+            destroy(dev);
+        }
+    };
+}
+
+full_scale_test!(full_scale_10_cycles, 10, 0xF6, 10240);
+full_scale_test!(full_scale_63_cycles, 63, 0xC1, 64512);
+full_scale_test!(full_scale_64_cycles, 64, 0xC0, 65535);
+full_scale_test!(full_scale_256_cycles, 256, 0x00, 65535);
+
+#[test]
+fn full_scale_follows_read_back_cycles() {
+    let mut dev = new(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![BitFlags::CMD | Register::ATIME],
+        vec![0xFE],
+    )]);
+    dev.read_integration_cycles().unwrap();
+    assert_eq!(2048, dev.full_scale_count());
+    destroy(dev);
+}
+
+#[test]
+fn can_classify_saturation() {
+    assert_eq!(Saturation::None, Saturation::classify(768, 1024));
+    assert_eq!(Saturation::Analog, Saturation::classify(769, 1024));
+    assert_eq!(Saturation::Analog, Saturation::classify(1023, 1024));
+    assert_eq!(Saturation::Digital, Saturation::classify(1024, 1024));
+    assert_eq!(Saturation::Digital, Saturation::classify(65535, 65535));
+}
+
+#[test]
+fn analog_saturation_only_for_short_integration_times() {
+    assert_eq!(Saturation::Analog, Saturation::classify(60000, 64512));
+    assert_eq!(Saturation::None, Saturation::classify(60000, 65535));
+    assert_eq!(Saturation::None, Saturation::classify(65534, 65535));
+}
+
+#[test]
+fn can_read_all_channels_with_saturation() {
+    let mut dev = new(&[I2cTrans::write_read(
+        DEV_ADDR,
+        vec![BitFlags::CMD | BitFlags::CMD_AUTO_INC | Register::CDATA],
+        vec![0x00, 0x04, 0x00, 0x01, 0xFF, 0x03, 0x00, 0x00],
+    )]);
+    let (measurement, saturation) = dev.read_all_channels_with_saturation().unwrap();
+    assert_eq!(1024, measurement.clear);
+    assert_eq!(
+        ChannelSaturation {
+            red: Saturation::None,
+            green: Saturation::Analog,
+            blue: Saturation::None,
+            clear: Saturation::Digital,
+        },
+        saturation
+    );
+    assert_eq!(Saturation::Digital, saturation.worst());
+    destroy(dev);
+}
+
+#[test]
+fn unsaturated_measurement() {
+    let m = AllChannelMeasurement {
+        red: 100,
+        green: 200,
+        blue: 300,
+        clear: 600,
+    };
+    assert_eq!(Saturation::None, m.saturation(65535).worst());
+}
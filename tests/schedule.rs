@@ -0,0 +1,91 @@
+mod common;
+use crate::common::{assert_close, destroy, new, write, BitFlags as BF, Register};
+use tcs3472::{DutyCycle, DutyCycleError};
+
+#[test]
+fn datasheet_example() {
+    let duty_cycle = DutyCycle::new(88_800, 43_200).unwrap();
+    assert_eq!(18, duty_cycle.integration_cycles());
+    assert_eq!(18, duty_cycle.wait_cycles());
+    assert!(!duty_cycle.wait_long());
+    assert_eq!(43_200, duty_cycle.integration_time_us());
+    assert_eq!(43_200, duty_cycle.wait_time_us());
+    assert_eq!(88_800, duty_cycle.period_us());
+    assert_close(152.3, duty_cycle.average_current_ua(), 0.1);
+}
+
+#[test]
+fn rounds_to_nearest_cycles() {
+    let duty_cycle = DutyCycle::new(100_000, 24_000).unwrap();
+    assert_eq!(10, duty_cycle.integration_cycles());
+    assert_eq!(31, duty_cycle.wait_cycles());
+    assert!(!duty_cycle.wait_long());
+    assert_eq!(100_800, duty_cycle.period_us());
+}
+
+#[test]
+fn uses_wait_long_for_long_periods() {
+    let duty_cycle = DutyCycle::new(1_000_000, 2400).unwrap();
+    assert_eq!(1, duty_cycle.integration_cycles());
+    assert_eq!(35, duty_cycle.wait_cycles());
+    assert!(duty_cycle.wait_long());
+    assert_eq!(1_012_800, duty_cycle.period_us());
+}
+
+#[test]
+fn uses_wait_long_only_if_closer() {
+    // 24 * 12 = 288 cycles cannot be set without wait long
+    let duty_cycle = DutyCycle::new(4800 + 691_200, 2400).unwrap();
+    assert_eq!(
+        (24, true),
+        (duty_cycle.wait_cycles(), duty_cycle.wait_long())
+    );
+    let duty_cycle = DutyCycle::new(4800 + 60 * 2400, 2400).unwrap();
+    assert_eq!(
+        (60, false),
+        (duty_cycle.wait_cycles(), duty_cycle.wait_long())
+    );
+}
+
+#[test]
+fn no_wait_for_shortest_period() {
+    let duty_cycle = DutyCycle::new(24_000, 21_600).unwrap();
+    assert_eq!(0, duty_cycle.wait_cycles());
+    assert_eq!(24_000, duty_cycle.period_us());
+    assert_close(235.0, duty_cycle.average_current_ua(), 0.1);
+}
+
+#[test]
+fn cannot_compute_invalid_duty_cycle() {
+    assert_eq!(
+        Err(DutyCycleError::IntegrationTime),
+        DutyCycle::new(1_000_000, 1000)
+    );
+    assert_eq!(
+        Err(DutyCycleError::IntegrationTime),
+        DutyCycle::new(1_000_000, 616_000)
+    );
+    assert_eq!(Err(DutyCycleError::Period), DutyCycle::new(20_000, 24_000));
+    assert_eq!(Err(DutyCycleError::Period), DutyCycle::new(8_000_000, 2400));
+}
+
+#[test]
+fn can_apply_duty_cycle() {
+    let mut sensor = new(&[
+        write(Register::ATIME, 0xF6),
+        write(Register::WTIME, 0xE1),
+        write(Register::CONFIG, 0),
+        write(Register::ENABLE, BF::WAIT_EN),
+    ]);
+    let duty_cycle = DutyCycle::new(100_000, 24_000).unwrap();
+    sensor.apply_duty_cycle(&duty_cycle).unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn can_apply_duty_cycle_without_wait() {
+    let mut sensor = new(&[write(Register::ATIME, 0xF7), write(Register::ENABLE, 0)]);
+    let duty_cycle = DutyCycle::new(24_000, 21_600).unwrap();
+    sensor.apply_duty_cycle(&duty_cycle).unwrap();
+    destroy(sensor);
+}
@@ -0,0 +1,144 @@
+mod common;
+use crate::common::{
+    apply_config, destroy, new, read, read_burst, write, write_burst, BitFlags as BF, Register,
+};
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use tcs3472::{registers::Control, Config, RgbCGain};
+
+const REGISTERS: [u8; 16] = [
+    BF::POWER_ON,
+    0xF6,
+    0,
+    0xAB,
+    0x00,
+    0x04,
+    0x00,
+    0xF0,
+    0,
+    0,
+    0,
+    0,
+    4,
+    BF::WLONG,
+    0,
+    2,
+];
+
+fn read_all(data: [u8; 16]) -> I2cTrans {
+    read_burst(Register::ENABLE, &data)
+}
+
+#[test]
+fn nothing_is_known_initially() {
+    let dev = new(&[]);
+    assert_eq!(None, dev.cached_register(Register::ENABLE));
+    assert_eq!(None, dev.cached_typed_register::<Control>());
+    assert_eq!(None, dev.cached_config());
+    destroy(dev);
+}
+
+#[test]
+fn first_write_goes_out_and_unchanged_writes_are_skipped() {
+    let mut dev = new(&[write(Register::CONTROL, 0), write(Register::CONTROL, 1)]);
+    dev.set_rgbc_gain(RgbCGain::_1x).unwrap();
+    dev.set_rgbc_gain(RgbCGain::_1x).unwrap();
+    dev.set_rgbc_gain(RgbCGain::_4x).unwrap();
+    dev.set_rgbc_gain(RgbCGain::_4x).unwrap();
+    assert_eq!(Some(1), dev.cached_register(Register::CONTROL));
+    assert_eq!(
+        Some(Control {
+            gain: RgbCGain::_4x
+        }),
+        dev.cached_typed_register()
+    );
+    destroy(dev);
+}
+
+#[test]
+fn failed_write_makes_register_unknown() {
+    let mut dev = new(&[
+        write(Register::CONTROL, 1),
+        write(Register::CONTROL, 2).with_error(ErrorKind::Other),
+        write(Register::CONTROL, 1),
+    ]);
+    dev.set_rgbc_gain(RgbCGain::_4x).unwrap();
+    dev.set_rgbc_gain(RgbCGain::_16x).unwrap_err();
+    assert_eq!(None, dev.cached_register(Register::CONTROL));
+    dev.set_rgbc_gain(RgbCGain::_4x).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn read_values_are_cached() {
+    let mut dev = new(&[read(Register::CONTROL, 3)]);
+    assert_eq!(RgbCGain::_60x, dev.read_rgbc_gain().unwrap());
+    dev.set_rgbc_gain(RgbCGain::_60x).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn unchanged_threshold_write_is_skipped() {
+    let mut dev = new(&[write_burst(Register::AILTL, &[0x34, 0x12])]);
+    dev.set_rgbc_interrupt_low_threshold(0x1234).unwrap();
+    dev.set_rgbc_interrupt_low_threshold(0x1234).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn applying_same_config_again_is_skipped() {
+    let config = Config::builder()
+        .gain(RgbCGain::_4x)
+        .integration_cycles(10)
+        .build()
+        .unwrap();
+    let mut dev = new(&apply_config(&config));
+    dev.apply_config(&config).unwrap();
+    assert_eq!(Some(config), dev.cached_config());
+    dev.apply_config(&config).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_sync_from_device() {
+    let mut dev = new(&[read_all(REGISTERS)]);
+    dev.sync_from_device().unwrap();
+    let config = dev.cached_config().unwrap();
+    assert_eq!(RgbCGain::_16x, config.gain());
+    assert_eq!(10, config.integration_cycles());
+    assert_eq!(1024, config.rgbc_interrupt_low_threshold());
+    assert_eq!(10240, dev.full_scale_count());
+    assert_eq!(None, dev.cached_register(0x02));
+    // nothing changed
+    dev.set_integration_cycles(10).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn verify_matches_unchanged_device() {
+    let mut dev = new(&[read_all(REGISTERS), read_all(REGISTERS)]);
+    dev.sync_from_device().unwrap();
+    assert!(dev.verify().unwrap());
+    destroy(dev);
+}
+
+#[test]
+fn verify_detects_drift() {
+    let mut reset = [0; 16];
+    reset[usize::from(Register::ATIME)] = 0xFF;
+    reset[usize::from(Register::WTIME)] = 0xFF;
+    let mut dev = new(&[read_all(REGISTERS), read_all(reset)]);
+    dev.sync_from_device().unwrap();
+    assert!(!dev.verify().unwrap());
+    // the shadow copy is unchanged
+    assert_eq!(Some(0xF6), dev.cached_register(Register::ATIME));
+    destroy(dev);
+}
+
+#[test]
+fn verify_only_compares_known_registers() {
+    let mut dev = new(&[write(Register::CONTROL, 2), read_all(REGISTERS)]);
+    dev.set_rgbc_gain(RgbCGain::_16x).unwrap();
+    assert!(dev.verify().unwrap());
+    destroy(dev);
+}
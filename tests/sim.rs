@@ -0,0 +1,308 @@
+#![cfg(feature = "sim")]
+use embedded_hal::i2c::{ErrorKind, I2c, NoAcknowledgeSource};
+use tcs3472::sim::{Light, Script, Simulator};
+use tcs3472::{
+    Config, DeviceVariant, DutyCycle, EnableFlags, Error, RgbCGain, RgbCInterruptPersistence,
+    RgbCInterruptTrigger, Tcs3472,
+};
+
+const LIGHT: Light = Light {
+    red: 100.0,
+    green: 200.0,
+    blue: 50.0,
+    clear: 400.0,
+};
+
+#[test]
+fn can_probe() {
+    let sim = Simulator::new_with_variant(DeviceVariant::Tcs34723, LIGHT);
+    let mut sensor = Tcs3472::new_with_variant(sim.i2c(), DeviceVariant::Tcs34723);
+    assert_eq!(DeviceVariant::Tcs34723, sensor.probe().unwrap());
+}
+
+#[test]
+fn wrong_address_is_not_acknowledged() {
+    let sim = Simulator::new(LIGHT);
+    let mut i2c = sim.i2c();
+    assert_eq!(
+        Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+        i2c.write(0x39, &[0x80])
+    );
+}
+
+#[test]
+fn missing_command_bit_is_not_acknowledged() {
+    let sim = Simulator::new(LIGHT);
+    let mut i2c = sim.i2c();
+    assert_eq!(
+        Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data)),
+        i2c.write(0x29, &[0x00, 0x01])
+    );
+}
+
+#[test]
+fn power_on_state() {
+    let sim = Simulator::new(LIGHT);
+    let mut sensor = Tcs3472::new(sim.i2c());
+    assert_eq!(Config::default(), sensor.read_config().unwrap());
+    assert!(!sensor.is_rgbc_status_valid().unwrap());
+}
+
+#[test]
+fn can_measure() {
+    let sim = Simulator::new(LIGHT);
+    let mut sensor = Tcs3472::new(sim.i2c());
+    sensor.set_rgbc_gain(RgbCGain::_4x).unwrap();
+    sensor.set_integration_cycles(10).unwrap();
+    let m = sensor.measure(&mut sim.delay(), true).unwrap();
+    // the clear channel saturates at 10 * 1024 counts
+    assert_eq!((4000, 10240, 8000, 2000), (m.red, m.clear, m.green, m.blue));
+    assert!(!sensor.read_enable_flags().unwrap().power_on);
+}
+
+#[test]
+fn can_measure_after_driver_reset() {
+    let sim = Simulator::new(LIGHT);
+    Tcs3472::new(sim.i2c()).set_integration_cycles(256).unwrap();
+    // a new driver does not know the integration time
+    let mut sensor = Tcs3472::new(sim.i2c());
+    let m = sensor.measure(&mut sim.delay(), false).unwrap();
+    assert_eq!(65535, m.clear);
+}
+
+#[test]
+fn measurement_saturates_at_full_scale() {
+    let sim = Simulator::new(LIGHT);
+    let mut sensor = Tcs3472::new(sim.i2c());
+    sensor.set_rgbc_gain(RgbCGain::_60x).unwrap();
+    let (m, saturation) = {
+        sensor.measure(&mut sim.delay(), false).unwrap();
+        sensor.read_all_channels_with_saturation().unwrap()
+    };
+    assert_eq!(1024, m.clear);
+    assert_eq!(1024, m.red);
+    assert_eq!(tcs3472::Saturation::Digital, saturation.worst());
+}
+
+#[test]
+fn data_is_valid_after_initialization_and_integration_time() {
+    let sim = Simulator::new(LIGHT);
+    let mut sensor = Tcs3472::new(sim.i2c());
+    sensor.set_integration_cycles(2).unwrap();
+    sensor.enable().unwrap();
+    sim.advance_us(2400);
+    sensor.enable_rgbc().unwrap();
+    sim.advance_us(2400 + 4799);
+    assert!(!sensor.is_rgbc_status_valid().unwrap());
+    sim.advance_us(1);
+    assert!(sensor.is_rgbc_status_valid().unwrap());
+    assert_eq!(800, sensor.read_clear_channel().unwrap());
+}
+
+#[test]
+fn nothing_is_measured_while_powered_off() {
+    let sim = Simulator::new(LIGHT);
+    let mut sensor = Tcs3472::new(sim.i2c());
+    sensor.enable_rgbc().unwrap();
+    sim.advance_us(100_000);
+    assert!(!sensor.is_rgbc_status_valid().unwrap());
+    assert_eq!(0, sensor.read_clear_channel().unwrap());
+}
+
+#[test]
+fn wait_time_extends_the_cycle() {
+    let sim = Simulator::new(Script::new(&[
+        (0, LIGHT),
+        (
+            5_000,
+            Light {
+                clear: 10.0,
+                ..LIGHT
+            },
+        ),
+    ]));
+    let mut sensor = Tcs3472::new(sim.i2c());
+    sensor.set_wait_cycles(10).unwrap();
+    sensor.enable_wait_long().unwrap();
+    sensor
+        .apply_config(
+            &Config::builder()
+                .wait_cycles(10)
+                .wait_long(true)
+                .enable_flags(EnableFlags {
+                    power_on: true,
+                    rgbc: true,
+                    wait: true,
+                    ..Default::default()
+                })
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+    // initialization and first integration
+    sim.advance_us(4800);
+    assert_eq!(400, sensor.read_clear_channel().unwrap());
+    // wait time of 10 * 12 * 2.4ms
+    sim.advance_us(288_000);
+    assert_eq!(400, sensor.read_clear_channel().unwrap());
+    // initialization of the next cycle
+    sim.advance_us(2400);
+    assert_eq!(400, sensor.read_clear_channel().unwrap());
+    sim.advance_us(2400);
+    assert_eq!(10, sensor.read_clear_channel().unwrap());
+}
+
+#[test]
+fn scene_follows_simulated_time() {
+    let sim = Simulator::new(|time_us: u64| Light {
+        clear: (time_us / 1000) as f32,
+        ..Default::default()
+    });
+    let mut sensor = Tcs3472::new(sim.i2c());
+    assert_eq!(
+        6,
+        sensor.measure(&mut sim.delay(), false).unwrap().clear,
+        "sampled in the middle of the integration at 6ms"
+    );
+    // warm-up, initialization and one integration cycle
+    assert_eq!(7200, sim.time_us());
+}
+
+#[test]
+fn interrupt_is_asserted_after_persistence() {
+    let sim = Simulator::new(LIGHT);
+    let mut sensor = Tcs3472::new(sim.i2c());
+    sensor
+        .configure_rgbc_interrupt(RgbCInterruptTrigger::Thresholds {
+            low: 500,
+            high: 1000,
+            persistence: RgbCInterruptPersistence::_3,
+        })
+        .unwrap();
+    sensor.enable().unwrap();
+    sensor.enable_rgbc().unwrap();
+    // each cycle consists of the initialization and the integration
+    sim.advance_us(2 * 4800);
+    assert!(!sensor.read_status().unwrap().rgbc_interrupt);
+    sim.advance_us(4800);
+    assert!(sensor.read_status().unwrap().rgbc_interrupt);
+    assert!(sim.is_interrupt_asserted());
+    sensor.clear_rgbc_interrupt().unwrap();
+    assert!(!sim.is_interrupt_asserted());
+    // the count starts again after clearing
+    sim.advance_us(2 * 4800);
+    assert!(!sim.is_interrupt_asserted());
+    sim.advance_us(4800);
+    assert!(sim.is_interrupt_asserted());
+}
+
+#[test]
+fn interrupt_is_not_asserted_within_thresholds() {
+    let sim = Simulator::new(LIGHT);
+    let mut sensor = Tcs3472::new(sim.i2c());
+    sensor
+        .configure_rgbc_interrupt(RgbCInterruptTrigger::Thresholds {
+            low: 300,
+            high: 500,
+            persistence: RgbCInterruptPersistence::_1,
+        })
+        .unwrap();
+    sensor.enable().unwrap();
+    sensor.enable_rgbc().unwrap();
+    sim.advance_us(100_000);
+    assert!(!sim.is_interrupt_asserted());
+}
+
+#[test]
+fn interrupt_on_every_cycle() {
+    let sim = Simulator::new(LIGHT);
+    let mut sensor = Tcs3472::new(sim.i2c());
+    sensor
+        .configure_rgbc_interrupt(RgbCInterruptTrigger::EveryCycle)
+        .unwrap();
+    sensor.measure(&mut sim.delay(), false).unwrap();
+    let (status, m) = sensor.service_rgbc_interrupt().unwrap();
+    assert!(status.rgbc_interrupt);
+    assert_eq!(400, m.clear);
+    assert!(!sim.is_interrupt_asserted());
+}
+
+#[test]
+fn can_recover_from_power_on_reset() {
+    let sim = Simulator::new(LIGHT);
+    let mut sensor = Tcs3472::new(sim.i2c());
+    let config = Config::builder()
+        .gain(RgbCGain::_16x)
+        .integration_cycles(4)
+        .enable_flags(EnableFlags {
+            power_on: true,
+            rgbc: true,
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+    sensor.apply_config(&config).unwrap();
+    assert_eq!(None, sensor.recover_from_reset().unwrap());
+    sim.power_on_reset();
+    assert!(sensor.recover_from_reset().unwrap().is_some());
+    assert_eq!(config, sensor.read_config().unwrap());
+    sim.advance_us(5 * 2400);
+    assert_eq!(3200, sensor.read_blue_channel().unwrap());
+}
+
+#[test]
+fn repeated_byte_reads_same_register() {
+    let sim = Simulator::new(LIGHT);
+    let mut i2c = sim.i2c();
+    let mut data = [0; 2];
+    i2c.write_read(0x29, &[0x80 | 0x01], &mut data).unwrap();
+    assert_eq!([0xFF, 0xFF], data);
+    i2c.write_read(0x29, &[0xA0 | 0x01], &mut data).unwrap();
+    assert_eq!([0xFF, 0x00], data);
+}
+
+#[test]
+fn read_only_registers_are_not_written() {
+    let sim = Simulator::new(LIGHT);
+    let mut sensor = Tcs3472::new(sim.i2c());
+    sensor.write_register(0x12, 0).unwrap();
+    assert_eq!(0x44, sim.register(0x12));
+    assert!(matches!(
+        sensor.read_device_variant(),
+        Ok(DeviceVariant::Tcs34725)
+    ));
+    assert!(!matches!(
+        sensor.read_rgbc_gain(),
+        Err(Error::InvalidRegisterValue)
+    ));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn can_measure_with_async_driver() {
+    let sim = Simulator::new(LIGHT);
+    let mut sensor = tcs3472::Tcs3472Async::new(sim.i2c());
+    let m = embassy_futures::block_on(sensor.measure(&mut sim.delay(), true)).unwrap();
+    assert_eq!(400, m.clear);
+    assert_eq!(7200, sim.time_us());
+}
+
+#[test]
+fn duty_cycle_period_is_achieved() {
+    // counts per cycle following the simulated time in ms
+    let sim = Simulator::new(|time_us: u64| Light {
+        clear: time_us as f32 / 1000.0,
+        ..Default::default()
+    });
+    let mut sensor = Tcs3472::new(sim.i2c());
+    let duty_cycle = DutyCycle::new(100_000, 24_000).unwrap();
+    sensor.apply_duty_cycle(&duty_cycle).unwrap();
+    sensor.enable().unwrap();
+    sensor.enable_rgbc().unwrap();
+    sim.advance_us(2400 + 24_000);
+    let first = sensor.read_clear_channel().unwrap();
+    sim.advance_us(duty_cycle.period_us().into());
+    let second = sensor.read_clear_channel().unwrap();
+    // 10 integration cycles sampled one period later
+    assert_eq!(duty_cycle.period_us() / 100, u32::from(second - first));
+}
@@ -0,0 +1,98 @@
+mod common;
+use crate::common::{destroy, m, new, read, read_all_channels, write, BitFlags as BF, Register};
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use tcs3472::{Error, RgbCGain, TypedTcs3472};
+
+fn write_enable(value: u8) -> I2cTrans {
+    write(Register::ENABLE, value)
+}
+
+#[test]
+fn new_powers_off_preserving_other_settings() {
+    let mut dev = new(&[
+        write_enable(BF::POWER_ON),
+        write_enable(BF::POWER_ON | BF::RGBC_EN),
+        write_enable(BF::POWER_ON | BF::RGBC_EN | BF::WAIT_EN),
+        write_enable(BF::WAIT_EN),
+        write_enable(BF::WAIT_EN | BF::POWER_ON),
+    ]);
+    dev.enable().unwrap();
+    dev.enable_rgbc().unwrap();
+    dev.enable_wait().unwrap();
+    let sensor = TypedTcs3472::new(dev).unwrap();
+    let sensor = sensor.into_idle().ok().unwrap();
+    sensor.destroy().done();
+}
+
+#[test]
+fn can_go_through_all_states() {
+    let dev = new(&[
+        write_enable(0),
+        write_enable(BF::POWER_ON),
+        write(Register::CONTROL, 1),
+        write_enable(BF::POWER_ON | BF::RGBC_EN),
+        read(Register::STATUS, BF::RGBC_VALID),
+        read_all_channels(&m(0x4567, 0x89AB, 0xCDEF, 0x0123)),
+        write_enable(BF::POWER_ON),
+        write_enable(0),
+    ]);
+    let sensor = TypedTcs3472::new(dev).unwrap();
+    let mut sensor = sensor.into_idle().ok().unwrap();
+    sensor.set_rgbc_gain(RgbCGain::_4x).unwrap();
+    let mut sensor = sensor.into_active().ok().unwrap();
+    assert!(sensor.is_rgbc_status_valid().unwrap());
+    let measurement = sensor.read_all_channels().unwrap();
+    assert_eq!(0x0123, measurement.clear);
+    assert_eq!(0xCDEF, measurement.blue);
+    let sensor = sensor.into_idle().ok().unwrap();
+    let sensor = sensor.into_off().ok().unwrap();
+    sensor.destroy().done();
+}
+
+#[test]
+fn can_power_off_from_active_in_one_write() {
+    let dev = new(&[
+        write_enable(0),
+        write_enable(BF::POWER_ON),
+        write_enable(BF::POWER_ON | BF::RGBC_EN),
+        write_enable(0),
+    ]);
+    let sensor = TypedTcs3472::new(dev).unwrap();
+    let sensor = sensor.into_idle().ok().unwrap();
+    let sensor = sensor.into_active().ok().unwrap();
+    let sensor = sensor.into_off().ok().unwrap();
+    sensor.destroy().done();
+}
+
+#[test]
+fn failed_transition_returns_device_in_previous_state() {
+    let dev = new(&[
+        write_enable(0),
+        write_enable(BF::POWER_ON).with_error(ErrorKind::Other),
+        write_enable(BF::POWER_ON),
+    ]);
+    let sensor = TypedTcs3472::new(dev).unwrap();
+    let err = sensor.into_idle().err().unwrap();
+    assert!(matches!(err.error, Error::I2C(_)));
+    let sensor = err.dev.into_idle().ok().unwrap();
+    sensor.destroy().done();
+}
+
+#[test]
+fn can_return_to_dynamic_api() {
+    let dev = new(&[write_enable(0), write_enable(BF::POWER_ON)]);
+    let sensor = TypedTcs3472::new(dev).unwrap();
+    let mut dev = sensor.into_inner();
+    dev.enable().unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn full_scale_count_follows_integration_cycles() {
+    let dev = new(&[write_enable(0), write(Register::ATIME, 0xC0)]);
+    let mut sensor = TypedTcs3472::new(dev).unwrap();
+    sensor.set_integration_cycles(64).unwrap();
+    assert_eq!(65535, sensor.full_scale_count());
+    sensor.destroy().done();
+}
@@ -0,0 +1,68 @@
+mod common;
+use crate::common::assert_close;
+use tcs3472::{AllChannelMeasurement, CalibrationMatrix, Xyz};
+
+const MEASUREMENT: AllChannelMeasurement = AllChannelMeasurement {
+    red: 1000,
+    green: 2000,
+    blue: 500,
+    clear: 4000,
+};
+
+#[test]
+fn can_convert_with_tcs3414_approximation() {
+    let xyz = CalibrationMatrix::tcs3414_approximation().to_xyz(&MEASUREMENT);
+    assert_close(2477.455, xyz.x, 1e-3);
+    assert_close(2466.125, xyz.y, 1e-3);
+    assert_close(1141.10, xyz.z, 1e-3);
+}
+
+#[test]
+fn can_convert_with_rgbc_matrix() {
+    let matrix = CalibrationMatrix::from_rgbc([
+        [1.0, 0.0, 0.0, 0.5],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, -0.25],
+    ]);
+    let xyz = matrix.to_xyz(&MEASUREMENT);
+    assert_eq!(
+        Xyz {
+            x: 3000.0,
+            y: 2000.0,
+            z: -500.0
+        },
+        xyz
+    );
+}
+
+#[test]
+fn rgb_matrix_ignores_clear_channel() {
+    let matrix = CalibrationMatrix::from_rgb([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+    assert_eq!(0.0, matrix.coefficients()[1][3]);
+    let xyz = matrix.to_xyz(&MEASUREMENT);
+    assert_eq!(1000.0, xyz.x);
+    assert_eq!(2000.0, xyz.y);
+    assert_eq!(500.0, xyz.z);
+}
+
+#[test]
+fn can_calculate_chromaticity() {
+    let xyz = Xyz {
+        x: 95.047,
+        y: 100.0,
+        z: 108.883,
+    };
+    let xy = xyz.chromaticity().unwrap();
+    assert_close(0.3127, xy.x, 1e-3);
+    assert_close(0.3290, xy.y, 1e-3);
+}
+
+#[test]
+fn dark_has_no_chromaticity() {
+    let xyz = Xyz {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+    assert!(xyz.chromaticity().is_none());
+}